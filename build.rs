@@ -0,0 +1,47 @@
+use std::fmt::Write;
+use std::path::Path;
+
+/// Generates one `#[test]` per entry of `answers.toml`, included by `src/answers.rs`.
+fn main() {
+    println!("cargo:rerun-if-changed=answers.toml");
+
+    let registry = std::fs::read_to_string("answers.toml").unwrap_or_default();
+    let table: toml::Table = registry.parse().expect("answers.toml is not valid TOML");
+
+    let mut tests = String::new();
+    for (day_key, parts) in &table {
+        let day = day_key
+            .strip_prefix("day")
+            .and_then(|n| n.parse::<u32>().ok())
+            .unwrap_or_else(|| panic!("Expected `dayN` in answers.toml, found `{}`", day_key));
+        for (part_key, inputs) in parts.as_table().into_iter().flatten() {
+            let part = part_key
+                .strip_prefix("part")
+                .and_then(|n| n.parse::<u32>().ok())
+                .unwrap_or_else(|| {
+                    panic!("Expected `partN` in answers.toml, found `{}`", part_key)
+                });
+            for (input, entry) in inputs.as_table().into_iter().flatten() {
+                let ignore = entry
+                    .get("ignore")
+                    .and_then(toml::Value::as_bool)
+                    .unwrap_or(false);
+                let name = input.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+
+                writeln!(tests, "#[test]").unwrap();
+                if ignore {
+                    writeln!(tests, "#[ignore]").unwrap();
+                }
+                writeln!(
+                    tests,
+                    "fn day{:02}_part{}_{}() -> Result<()> {{ assert_correct({}, {}, {:?}) }}",
+                    day, part, name, day, part, input
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    std::fs::write(Path::new(&out_dir).join("answer_tests.rs"), tests).unwrap();
+}
@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+use crate::{dispatch, Solution};
+
+/// Registry the runner checks its answers against, relative to the working directory.
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// A verified answer for one part of a day on one input file next to the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub day: u32,
+    pub part: u32,
+    pub input: String,
+    pub expected: i64,
+    /// Left out of the default test run, e.g. because it is slow.
+    pub ignore: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Entry {
+    Answer(i64),
+    Detailed {
+        answer: i64,
+        #[serde(default)]
+        ignore: bool,
+    },
+}
+
+type Table = BTreeMap<String, BTreeMap<String, BTreeMap<String, Entry>>>;
+
+/// Known answers from a file of `[dayNN.partN]` tables mapping input files to answers.
+#[derive(Debug, Clone, Default)]
+pub struct Answers {
+    answers: Vec<Answer>,
+    /// Directory the input file names are relative to, that of the registry file.
+    dir: PathBuf,
+}
+
+/// Outcome of comparing an answer with the registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Unknown,
+    Correct,
+    Wrong { expected: i64 },
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Unknown => Ok(()),
+            Verdict::Correct => write!(f, "✓"),
+            Verdict::Wrong { expected } => write!(f, "✗ (expected {})", expected),
+        }
+    }
+}
+
+fn number(key: &str, prefix: &str) -> Result<u32> {
+    key.strip_prefix(prefix)
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| anyhow!("Expected `{}N` in answers, found `{}`", prefix, key))
+}
+
+impl FromStr for Answers {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let table: Table = toml::from_str(s)?;
+
+        let mut answers = vec![];
+        for (day_key, parts) in table {
+            let day = number(&day_key, "day")?;
+            for (part_key, inputs) in parts {
+                let part = number(&part_key, "part")?;
+                for (input, entry) in inputs {
+                    let (expected, ignore) = match entry {
+                        Entry::Answer(answer) => (answer, false),
+                        Entry::Detailed { answer, ignore } => (answer, ignore),
+                    };
+                    answers.push(Answer {
+                        day,
+                        part,
+                        input,
+                        expected,
+                        ignore,
+                    });
+                }
+            }
+        }
+
+        Ok(Answers {
+            answers,
+            dir: PathBuf::new(),
+        })
+    }
+}
+
+impl Answers {
+    /// Reads the registry at `path`, which is empty if the file does not exist.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Ok(Answers::default());
+        }
+        let answers: Answers = std::fs::read_to_string(path)?
+            .parse()
+            .with_context(|| format!("Invalid answers in {}", path.display()))?;
+        Ok(Answers {
+            dir: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            ..answers
+        })
+    }
+
+    /// Where the input file of `answer` is, relative to the working directory.
+    pub fn path(&self, answer: &Answer) -> PathBuf {
+        self.dir.join(&answer.input)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Answer> {
+        self.answers.iter()
+    }
+
+    /// Looks up the input file at path `input`, which must be the registry's own file:
+    /// `./day01_p1_in` finds `day01_p1_in`, but a `day01_p1_in` elsewhere finds nothing.
+    pub fn get(&self, day: u32, part: u32, input: &str) -> Option<&Answer> {
+        let input = std::fs::canonicalize(input).ok()?;
+        self.answers.iter().find(|a| {
+            a.day == day
+                && a.part == part
+                && std::fs::canonicalize(self.path(a)).is_ok_and(|known| known == input)
+        })
+    }
+
+    pub fn verdict(&self, day: u32, part: u32, input: &str, answer: i64) -> Verdict {
+        match self.get(day, part, input) {
+            None => Verdict::Unknown,
+            Some(known) if known.expected == answer => Verdict::Correct,
+            Some(known) => Verdict::Wrong {
+                expected: known.expected,
+            },
+        }
+    }
+}
+
+fn solve<S: Solution>(part: u32, input: &str) -> Result<i64> {
+    match part {
+        1 => S::solve_part1(input),
+        2 => S::solve_part2(input),
+        part => Err(anyhow!("No part {}", part)),
+    }
+}
+
+/// Solves `part` of `day` on the input file next to the registry and compares it with
+/// the registry entry.
+pub fn check(day: u32, part: u32, input: &str) -> Result<Verdict> {
+    let answers = Answers::load(ANSWERS_FILE)?;
+    let path = answers.dir.join(input);
+    let input_text = std::fs::read_to_string(&path)?;
+    let answer = dispatch!(day, solve(part, &input_text))?;
+
+    Ok(answers.verdict(day, part, &path.to_string_lossy(), answer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One test per registry entry, generated by `build.rs` from `answers.toml`.
+    mod registry {
+        use super::*;
+
+        fn assert_correct(day: u32, part: u32, input: &str) -> Result<()> {
+            assert_eq!(check(day, part, input)?, Verdict::Correct);
+            Ok(())
+        }
+
+        include!(concat!(env!("OUT_DIR"), "/answer_tests.rs"));
+    }
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let answers: Answers =
+            "[day07.part2]\nday07_p1_ex = 5905\nday07_p1_in = { answer = 1, ignore = true }\n"
+                .parse()?;
+        assert_eq!(answers.iter().count(), 2);
+        let known = answers.get(7, 2, "./day07_p1_in").unwrap();
+        assert_eq!((known.expected, known.ignore), (1, true));
+        assert_eq!(answers.verdict(7, 2, "day07_p1_ex", 5905), Verdict::Correct);
+        assert_eq!(
+            answers.verdict(7, 2, "day07_p1_ex", 5),
+            Verdict::Wrong { expected: 5905 }
+        );
+        assert_eq!(answers.verdict(7, 1, "day07_p1_ex", 5905), Verdict::Unknown);
+        Ok(())
+    }
+
+    #[test]
+    fn test_same_name_elsewhere_is_unknown() -> Result<()> {
+        let answers: Answers = "[day07.part1]\nday07_p1_ex = 6440\n".parse()?;
+        let dir = std::env::temp_dir().join(format!("aoc23-answers-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let elsewhere = dir.join("day07_p1_ex");
+        std::fs::copy("day07_p1_ex", &elsewhere)?;
+        let verdict = answers.verdict(7, 1, &elsewhere.to_string_lossy(), 1);
+        std::fs::remove_dir_all(&dir)?;
+        assert_eq!(verdict, Verdict::Unknown);
+        assert_eq!(
+            answers.verdict(7, 1, "day07_p1_ex", 1),
+            Verdict::Wrong { expected: 6440 }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_bad_key() {
+        assert!("[day07.second]\nex = 1\n".parse::<Answers>().is_err());
+    }
+}
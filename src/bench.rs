@@ -0,0 +1,227 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::Solution;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// System allocator that counts every allocation, install it with `#[global_allocator]`.
+///
+/// Without it installed all allocation counts stay at zero.
+pub struct CountingAlloc;
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Timings and allocation counts of one stage over all iterations, sorted ascending.
+#[derive(Debug, Clone, Default)]
+pub struct Samples {
+    durations: Vec<Duration>,
+    allocations: Vec<usize>,
+}
+
+impl Samples {
+    fn measure<T>(&mut self, f: impl FnOnce() -> T) -> T {
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+        let start = Instant::now();
+        let ret = f();
+        self.durations.push(start.elapsed());
+        self.allocations
+            .push(ALLOCATIONS.load(Ordering::Relaxed) - allocations);
+        ret
+    }
+
+    fn finish(&mut self) {
+        self.durations.sort();
+        self.allocations.sort();
+    }
+
+    pub fn min(&self) -> Duration {
+        self.durations.first().copied().unwrap_or_default()
+    }
+
+    pub fn median(&self) -> Duration {
+        self.durations
+            .get(self.durations.len() / 2)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn max(&self) -> Duration {
+        self.durations.last().copied().unwrap_or_default()
+    }
+
+    pub fn allocations(&self) -> usize {
+        self.allocations
+            .get(self.allocations.len() / 2)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DayBench {
+    pub day: u32,
+    pub iterations: usize,
+    pub parse: Samples,
+    pub part1: Samples,
+    pub part2: Samples,
+}
+
+impl DayBench {
+    pub fn stages(&self) -> [(&'static str, &Samples); 3] {
+        [
+            ("parse", &self.parse),
+            ("part1", &self.part1),
+            ("part2", &self.part2),
+        ]
+    }
+
+    /// Median of a full run, parsing once and solving both parts.
+    pub fn total(&self) -> Duration {
+        self.stages().iter().map(|(_, s)| s.median()).sum()
+    }
+}
+
+/// Parses and solves both parts `iterations` times, timing each stage separately.
+///
+/// Each part is solved on its own input; when they differ the parse stage parses both.
+pub fn bench<S: Solution>(
+    day: u32,
+    part1: &str,
+    part2: &str,
+    iterations: usize,
+) -> Result<DayBench> {
+    let mut bench = DayBench {
+        day,
+        iterations,
+        parse: Samples::default(),
+        part1: Samples::default(),
+        part2: Samples::default(),
+    };
+
+    let shared = part1 == part2;
+    for _ in 0..iterations.max(1) {
+        let (parsed1, parsed2) = bench.parse.measure(|| -> Result<_> {
+            let parsed1 = S::parse(part1)?;
+            let parsed2 = match shared {
+                true => None,
+                false => Some(S::parse(part2)?),
+            };
+            Ok((parsed1, parsed2))
+        })?;
+        bench.part1.measure(|| S::part1(&parsed1))?;
+        bench.part2
+            .measure(|| S::part2(parsed2.as_ref().unwrap_or(&parsed1)))?;
+    }
+    bench.parse.finish();
+    bench.part1.finish();
+    bench.part2.finish();
+
+    Ok(bench)
+}
+
+/// Summary table of several days, one row per stage and the total of all medians.
+pub struct Report<'a>(pub &'a [DayBench]);
+
+impl Display for Report<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:>3}  {:<5}  {:>10}  {:>10}  {:>10}  {:>10}",
+            "day", "stage", "min", "median", "max", "allocs"
+        )?;
+        for day in self.0 {
+            for (stage, samples) in day.stages() {
+                writeln!(
+                    f,
+                    "{:>3}  {:<5}  {:>10}  {:>10}  {:>10}  {:>10}",
+                    format!("{:02}", day.day),
+                    stage,
+                    format!("{:.1?}", samples.min()),
+                    format!("{:.1?}", samples.median()),
+                    format!("{:.1?}", samples.max()),
+                    samples.allocations()
+                )?;
+            }
+        }
+        let total = self.0.iter().map(DayBench::total).sum::<Duration>();
+        write!(
+            f,
+            "{:>3}  {:<5}  {:>10}  {:>10}",
+            "all",
+            "total",
+            "",
+            format!("{:.1?}", total)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day06::RaceTable;
+    use crate::inputs::{locate, InputFile, Kind};
+
+    #[test]
+    fn test_bench_runs_every_stage() -> Result<()> {
+        let input = std::fs::read_to_string(locate(InputFile::new(6, 1, Kind::Example))?)?;
+        let bench = bench::<RaceTable>(6, &input, &input, 3)?;
+        for (_, samples) in bench.stages() {
+            assert_eq!(samples.durations.len(), 3);
+            assert!(samples.min() <= samples.median() && samples.median() <= samples.max());
+        }
+        Ok(())
+    }
+
+    /// Fails a part unless it is solved on the input named after it.
+    struct Named;
+
+    impl Solution for Named {
+        type Input = String;
+
+        fn parse(input: &str) -> Result<String> {
+            Ok(input.to_string())
+        }
+
+        fn part1(input: &String) -> Result<i64> {
+            anyhow::ensure!(input == "one", "part 1 solved on {}", input);
+            Ok(1)
+        }
+
+        fn part2(input: &String) -> Result<i64> {
+            anyhow::ensure!(input == "two", "part 2 solved on {}", input);
+            Ok(2)
+        }
+    }
+
+    #[test]
+    fn test_bench_solves_each_part_on_its_input() -> Result<()> {
+        assert!(bench::<Named>(0, "one", "one", 1).is_err());
+        let bench = bench::<Named>(0, "one", "two", 2)?;
+        assert_eq!(bench.part2.durations.len(), 2);
+        Ok(())
+    }
+}
@@ -1,72 +1,96 @@
+use anyhow::Result;
 use onig::Regex;
 
-fn part1(input: &'static str) -> i64 {
-    std::fs::read_to_string(input)
-        .expect("Could not read file")
-        .split("\n")
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let re = Regex::new(r"\d").unwrap();
-            let vals = re
-                .captures_iter(&line)
-                .map(|m| m.at(0).unwrap())
-                .map(|s| match s {
-                    _ => s,
-                })
-                .map(|s| {
-                    s.parse::<i64>()
-                        .expect(format!("Could not parse int from string {}", s).as_str())
-                });
-            let mut pa = vals.peekable();
-
-            let f = *pa.peek().unwrap_or(&0);
-            let l = pa.last().unwrap_or(0);
-            let lg = 10i64.pow(l.ilog10() + 1);
-            f * lg + l
-        })
-        .inspect(|x| println!("x: {:?}", x))
-        .sum()
+use log::trace;
+
+use crate::error::ParseError;
+use crate::Solution;
+
+const DAY: u32 = 1;
+
+#[derive(Debug, Clone)]
+pub struct CalibrationDocument {
+    /// Every line of the input, so that a line's index is its number less one.
+    pub lines: Vec<String>,
 }
 
-fn part2(input: &'static str) -> i64 {
-    std::fs::read_to_string(input)
-        .expect("Could not read file")
-        .split("\n")
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let re = Regex::new(r"(?=(\d|one|two|three|four|five|six|seven|eight|nine))").unwrap();
-            let vals = re
-                .captures_iter(&line)
-                .map(|m| m.at(1).unwrap())
-                // .inspect(|x| println!("x: {:?} line {:?}", x, line))
-                .map(|s| match s {
-                    "one" => "1",
-                    "two" => "2",
-                    "three" => "3",
-                    "four" => "4",
-                    "five" => "5",
-                    "six" => "6",
-                    "seven" => "7",
-                    "eight" => "8",
-                    "nine" => "9",
-                    _ => s,
-                })
-                .map(|s| {
-                    s.parse::<i64>()
-                        .expect(format!("Could not parse int from string \"{}\"", s).as_str())
-                });
-            let mut pa = vals.peekable();
-
-            let f = *pa.peek().unwrap_or(&0);
-            let l = pa.last().unwrap_or(0);
-            let lg = 10i64.pow(l.ilog10() + 1);
-            f * lg + l
+/// The digits on `line` in order, including spelled out ones like `eight` if `spelled`.
+fn digits(line: &str, spelled: bool) -> Vec<i64> {
+    let re = match spelled {
+        true => Regex::new(r"(?=(\d|one|two|three|four|five|six|seven|eight|nine))").unwrap(),
+        false => Regex::new(r"(\d)").unwrap(),
+    };
+    re.captures_iter(line)
+        .filter_map(|m| match m.at(1)? {
+            "one" => Some(1),
+            "two" => Some(2),
+            "three" => Some(3),
+            "four" => Some(4),
+            "five" => Some(5),
+            "six" => Some(6),
+            "seven" => Some(7),
+            "eight" => Some(8),
+            "nine" => Some(9),
+            s => s.parse().ok(),
         })
-        .inspect(|x| println!("x: {:?}", x))
-        .sum()
+        .collect()
+}
+
+impl CalibrationDocument {
+    /// The first and last digit of each line put together, e.g. `a1b2c3` gives 13.
+    fn values(&self, spelled: bool) -> Result<Vec<i64>, ParseError> {
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(i, line)| {
+                let digits = digits(line, spelled);
+                match (digits.first(), digits.last()) {
+                    (Some(first), Some(last)) => Ok(first * 10 + last),
+                    _ => Err(ParseError::new(DAY, i + 1, 1, "a line with a digit", line)),
+                }
+            })
+            .collect()
+    }
 }
 
-fn main() {}
+impl Solution for CalibrationDocument {
+    type Input = CalibrationDocument;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let document = CalibrationDocument {
+            lines: input.lines().map(|line| line.to_string()).collect(),
+        };
+        // Every line needs a digit, if only a spelled out one for part 2.
+        document.values(true)?;
+
+        Ok(document)
+    }
+
+    fn part1(input: &Self::Input) -> Result<i64> {
+        Ok(input
+            .values(false)?
+            .into_iter()
+            .inspect(|x| trace!("calibration value: {}", x))
+            .sum())
+    }
+
+    fn part2(input: &Self::Input) -> Result<i64> {
+        Ok(input
+            .values(true)?
+            .into_iter()
+            .inspect(|x| trace!("calibration value: {}", x))
+            .sum())
+    }
+}
+
+pub fn solve_part1(input: &str) -> Result<i64> {
+    CalibrationDocument::solve_part1(input)
+}
+
+pub fn solve_part2(input: &str) -> Result<i64> {
+    CalibrationDocument::solve_part2(input)
+}
 
 #[cfg(test)]
 mod tests {
@@ -82,22 +106,14 @@ mod tests {
     }
 
     #[test]
-    fn test_part1_ex() {
-        assert_eq!(part1("day01_p1_ex"), 142);
-    }
+    fn test_line_without_digits() {
+        let err = solve_part2("two1nine\nnothing here\n").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.snippet, "nothing here");
 
-    #[test]
-    fn test_part1_in() {
-        assert_eq!(part1("day01_p1_in"), 55172);
-    }
-
-    #[test]
-    fn test_part2_ex() {
-        assert_eq!(part2("day01_p2_ex"), 281);
-    }
-
-    #[test]
-    fn test_part2_in() {
-        assert_eq!(part2("day01_p2_in"), 54925);
+        let err = solve_part1("a0b\neightwothree\n").unwrap_err();
+        assert_eq!(err.downcast_ref::<ParseError>().unwrap().line, 2);
+        assert_eq!(solve_part1("a0b\n7\n").unwrap(), 77);
     }
 }
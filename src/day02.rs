@@ -7,15 +7,22 @@ use anyhow::Result;
 use pest::Parser;
 use pest_derive::Parser;
 
+use log::trace;
+
+use crate::error::{parse_int, ParseError};
+use crate::Solution;
+
+const DAY: u32 = 2;
+
 #[derive(Parser)]
 #[grammar = "day02.pest"]
 struct TokenParser;
 
-#[derive(Debug)]
-struct Draw {
-    red: i64,
-    green: i64,
-    blue: i64,
+#[derive(Debug, Clone)]
+pub struct Draw {
+    pub red: i64,
+    pub green: i64,
+    pub blue: i64,
 }
 
 impl Display for Draw {
@@ -27,20 +34,21 @@ impl Display for Draw {
         )
     }
 }
-struct Game {
-    num: i64,
-    draws: Vec<Draw>,
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub num: i64,
+    pub draws: Vec<Draw>,
 }
 
 impl Game {
-    fn is_possible(&self) -> bool {
+    pub fn is_possible(&self) -> bool {
         !self
             .draws
             .iter()
             .any(|draw| draw.red > 12 || draw.green > 13 || draw.blue > 14)
     }
 
-    fn power(&self) -> i64 {
+    pub fn power(&self) -> i64 {
         let vals = self.draws.iter().fold((0, 0, 0), |acc, draw| {
             (
                 acc.0.max(draw.red),
@@ -64,153 +72,116 @@ impl Display for Game {
     }
 }
 
-fn part1(input: &'static str) -> i64 {
-    let games = std::fs::read_to_string(input)
-        .expect("Could not read file")
-        .split("\n")
-        .filter(|line| !line.is_empty())
-        .inspect(|line| println!("line: {:?}", line))
-        .map(|line| {
-            let mut pairs = TokenParser::parse(Rule::game, line)
-                .unwrap_or_else(|e| panic!("{}", e))
-                .inspect(|game| {
-                    if line.len()
-                        != (game.as_span().end_pos().line_col().1
-                            - game.as_span().start_pos().line_col().1)
-                    {
-                        panic!("Line not fully parsed: {:?}\n\n {:?}", game, line);
-                    }
-                })
-                .next()
-                .unwrap()
-                .into_inner();
-
-            let num = pairs.next().unwrap().as_str().parse::<i64>().unwrap();
-            let draws = pairs
-                .inspect(|pair| println!("pair: {:?}", pair))
-                .map(|pair| {
-                    let mut color_pair_iter = pair.into_inner();
-                    let mut map = std::collections::HashMap::new();
-
-                    while let Some(color_pair) = color_pair_iter.next() {
-                        let mut color_pair_inner = color_pair.into_inner();
-                        let num = color_pair_inner
-                            .next()
-                            .unwrap()
-                            .as_str()
-                            .parse::<i64>()
-                            .unwrap();
-                        let color = color_pair_inner.next().unwrap().as_str();
-                        match color {
-                            "red" => map.insert("red", num),
-                            "green" => map.insert("green", num),
-                            "blue" => map.insert("blue", num),
-                            _ => panic!("Expected color"),
-                        };
-                    }
-                    Draw {
-                        red: *map.get("red").unwrap_or(&0),
-                        green: *map.get("green").unwrap_or(&0),
-                        blue: *map.get("blue").unwrap_or(&0),
-                    }
-                })
-                .collect::<Vec<Draw>>();
-
-            Game { num, draws }
-        })
-        .collect::<Vec<Game>>();
-
-    games
-        .iter()
-        .filter(|game| game.is_possible())
-        .fold(0, |acc, game| acc + game.num)
+#[derive(Debug, Clone)]
+pub struct GameRecord {
+    pub games: Vec<Game>,
+}
+
+impl Solution for GameRecord {
+    type Input = GameRecord;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let games = input
+            .split('\n')
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .inspect(|(_, line)| trace!("line: {:?}", line))
+            .map(|(line_idx, line)| -> Result<Game, ParseError> {
+                let game = TokenParser::parse(Rule::game, line)
+                    .map_err(|e| ParseError::from_pest(DAY, e).on_line(line_idx + 1))?
+                    .next()
+                    .ok_or_else(|| ParseError::new(DAY, line_idx + 1, 1, "game", line))?;
+                if game.as_span().end() != line.len() {
+                    return Err(ParseError::at_offset(
+                        DAY,
+                        line,
+                        game.as_span().end(),
+                        "\"; \" or end of line",
+                    )
+                    .on_line(line_idx + 1));
+                }
+                let mut pairs = game.into_inner();
+
+                let num = pairs
+                    .next()
+                    .map(|pair| parse_int(DAY, &pair))
+                    .ok_or_else(|| ParseError::new(DAY, line_idx + 1, 1, "game number", line))?
+                    .map_err(|e| e.on_line(line_idx + 1))?;
+                let draws = pairs
+                    .inspect(|pair| trace!("pair: {:?}", pair))
+                    .map(|pair| {
+                        let color_pair_iter = pair.into_inner();
+                        let mut map = std::collections::HashMap::new();
+
+                        for color_pair in color_pair_iter {
+                            let mut color_pair_inner = color_pair.clone().into_inner();
+                            let (Some(num), Some(color)) =
+                                (color_pair_inner.next(), color_pair_inner.next())
+                            else {
+                                return Err(ParseError::from_pair(
+                                    DAY,
+                                    &color_pair,
+                                    "count and color",
+                                ));
+                            };
+                            let num = parse_int(DAY, &num)?;
+                            match color.as_str() {
+                                "red" => map.insert("red", num),
+                                "green" => map.insert("green", num),
+                                "blue" => map.insert("blue", num),
+                                _ => return Err(ParseError::from_pair(DAY, &color, "color")),
+                            };
+                        }
+                        Ok(Draw {
+                            red: *map.get("red").unwrap_or(&0),
+                            green: *map.get("green").unwrap_or(&0),
+                            blue: *map.get("blue").unwrap_or(&0),
+                        })
+                    })
+                    .collect::<Result<Vec<Draw>, ParseError>>()
+                    .map_err(|e| e.on_line(line_idx + 1))?;
+
+                Ok(Game { num, draws })
+            })
+            .collect::<Result<Vec<Game>, ParseError>>()?;
+
+        Ok(GameRecord { games })
+    }
+
+    fn part1(input: &Self::Input) -> Result<i64> {
+        Ok(input
+            .games
+            .iter()
+            .filter(|game| game.is_possible())
+            .fold(0, |acc, game| acc + game.num))
+    }
+
+    fn part2(input: &Self::Input) -> Result<i64> {
+        Ok(input
+            .games
+            .iter()
+            // .filter(|game| game.is_possible())
+            .fold(0, |acc, game| acc + game.power()))
+    }
 }
 
-fn part2(input: &'static str) -> i64 {
-    let games = std::fs::read_to_string(input)
-        .expect("Could not read file")
-        .split("\n")
-        .filter(|line| !line.is_empty())
-        .inspect(|line| println!("line: {:?}", line))
-        .map(|line| {
-            let mut pairs = TokenParser::parse(Rule::game, line)
-                .unwrap_or_else(|e| panic!("{}", e))
-                .inspect(|game| {
-                    if line.len()
-                        != (game.as_span().end_pos().line_col().1
-                            - game.as_span().start_pos().line_col().1)
-                    {
-                        panic!("Line not fully parsed: {:?}\n\n {:?}", game, line);
-                    }
-                })
-                .next()
-                .unwrap()
-                .into_inner();
-
-            let num = pairs.next().unwrap().as_str().parse::<i64>().unwrap();
-            let draws = pairs
-                .inspect(|pair| println!("pair: {:?}", pair))
-                .map(|pair| {
-                    let mut color_pair_iter = pair.into_inner();
-                    let mut map = std::collections::HashMap::new();
-
-                    while let Some(color_pair) = color_pair_iter.next() {
-                        let mut color_pair_inner = color_pair.into_inner();
-                        let num = color_pair_inner
-                            .next()
-                            .unwrap()
-                            .as_str()
-                            .parse::<i64>()
-                            .unwrap();
-                        let color = color_pair_inner.next().unwrap().as_str();
-                        match color {
-                            "red" => map.insert("red", num),
-                            "green" => map.insert("green", num),
-                            "blue" => map.insert("blue", num),
-                            _ => panic!("Expected color"),
-                        };
-                    }
-                    Draw {
-                        red: *map.get("red").unwrap_or(&0),
-                        green: *map.get("green").unwrap_or(&0),
-                        blue: *map.get("blue").unwrap_or(&0),
-                    }
-                })
-                .collect::<Vec<Draw>>();
-
-            Game { num, draws }
-        })
-        .collect::<Vec<Game>>();
-
-    games
-        .iter()
-        // .filter(|game| game.is_possible())
-        .fold(0, |acc, game| acc + game.power())
+pub fn solve_part1(input: &str) -> Result<i64> {
+    GameRecord::solve_part1(input)
 }
 
-fn main() {}
+pub fn solve_part2(input: &str) -> Result<i64> {
+    GameRecord::solve_part2(input)
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_part1_ex() {
-        assert_eq!(part1("day02_p1_ex"), 8);
-    }
-
-    #[test]
-    fn test_part1_in() {
-        assert_eq!(part1("day02_p1_in"), 2162);
-    }
-
-    #[test]
-    fn test_part2_ex() {
-        assert_eq!(part2("day02_p2_ex"), 2286);
-    }
-
-    #[test]
-    fn test_part2_in() {
-        assert_eq!(part2("day02_p1_in"), 72513);
+    fn test_parse_error() {
+        let err = solve_part1("Game 1: 3 blue\nGame 2: 4 purple, 1 red\n").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (2, 11));
+        assert_eq!(err.expected, "color");
     }
 }
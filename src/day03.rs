@@ -7,152 +7,115 @@ use anyhow::{anyhow, Result};
 use pest::Parser;
 use pest_derive::Parser;
 
-struct PartNum<'a> {
-    hay: &'a str,
-    idx: usize,
-    width: usize,
-}
+use log::trace;
 
-impl<'a> PartNum<'a> {
-    fn new(hay: &'a str, idx: usize, width: usize) -> PartNum<'a> {
-        Self { idx, hay, width }
-    }
+use crate::error::ParseError;
+use crate::grid::{Grid, Pos};
+use crate::Solution;
 
-    fn num(&self) -> Result<i64> {
-        self.hay[self.idx..]
-            .chars()
-            .take_while(|c| c.is_digit(10))
-            .collect::<String>()
-            .parse()
-            .map_err(|e| anyhow!("Could not parse partnum: {}", e))
-    }
+const DAY: u32 = 3;
 
-    fn is_symbol(sym: char) -> bool {
-        match sym {
-            '0'..='9' => false,
-            '.' => false,
-            _ => true,
-        }
-    }
+/// A number in the schematic, starting at `pos` and `len` digits long.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartNum {
+    pub num: i64,
+    pub pos: Pos,
+    pub len: usize,
+}
 
-    fn is_adjacent(&self, idx: usize) -> Result<bool> {
-        let len = self.num()?.ilog10() as usize + 1;
-        Ok(vec![
-            vec![self.idx - 1usize],
-            vec![self.idx + len],
-            ((self.idx - 1usize - self.width)..(self.idx + len + 1usize - self.width)).collect(),
-            ((self.idx - 1usize + self.width)..(self.idx + len + 1usize + self.width)).collect(),
-        ]
-        .iter()
-        .flatten()
-        .any(|adjacent_usize| idx == *adjacent_usize))
+impl PartNum {
+    pub fn is_symbol(sym: char) -> bool {
+        !matches!(sym, '0'..='9' | '.')
     }
 
-    fn is_valid(&self) -> Result<bool> {
-        Ok(self
-            .hay
-            .chars()
-            .enumerate()
-            .any(|(i, c)| Self::is_symbol(c) && self.is_adjacent(i).unwrap_or(false)))
+    pub fn digits(&self) -> impl Iterator<Item = Pos> + '_ {
+        (self.pos.0..self.pos.0 + self.len).map(|x| (x, self.pos.1))
     }
-}
 
-fn part1(input: &'static str) -> Result<i64> {
-    let file: String = std::fs::read_to_string(input).expect("Could not read file");
-    let schematic_width = file.find('\n').unwrap() + 2;
-
-    let file = file.replace("\n", "..");
-    let file = ".".repeat(schematic_width + 1) + &file + &".".repeat(schematic_width - 1);
-
-    // std::fs::write("day03_p1_out", &file).expect("Could not write out file");
-
-    let mut it = file.chars().enumerate();
-    let mut res = 0;
-    while let Some(partnum) = (&mut it)
-        .skip_while(|(_, c)| c.is_digit(10))
-        .skip_while(|(_, c)| !c.is_digit(10))
-        .take(1)
-        .map(|(i, _)| PartNum::new(&file, i, schematic_width))
-        .nth(0)
-    {
-        println!("{}:{}", partnum.num().unwrap_or(0), partnum.is_valid()?);
-        if partnum.is_valid()? {
-            res += partnum.num()?;
-        }
+    /// Whether `pos` touches one of the digits, diagonals included.
+    pub fn is_adjacent(&self, (x, y): Pos) -> bool {
+        let (start, row) = self.pos;
+        x + 1 >= start && x <= start + self.len && y + 1 >= row && y <= row + 1
     }
 
-    Ok(res)
-}
-
-fn part2(input: &'static str) -> Result<i64> {
-    let file: String = std::fs::read_to_string(input).expect("Could not read file");
-    let schematic_width = file.find('\n').unwrap() + 2;
-
-    let file = file.replace("\n", "..");
-    let file = ".".repeat(schematic_width + 1) + &file + &".".repeat(schematic_width - 1);
-
-    // std::fs::write("day03_p1_out", &file).expect("Could not write out file");
-
-    let mut it = file.chars().enumerate();
-    let mut parts = vec![];
-    while let Some(partnum) = (&mut it)
-        .skip_while(|(_, c)| c.is_digit(10))
-        .skip_while(|(_, c)| !c.is_digit(10))
-        .take(1)
-        .map(|(i, _)| PartNum::new(&file, i, schematic_width))
-        .nth(0)
-    {
-        println!("{}:{}", partnum.num().unwrap_or(0), partnum.is_valid()?);
-        parts.push(partnum);
+    pub fn is_valid(&self, grid: &Grid<char>) -> bool {
+        self.digits()
+            .flat_map(|digit| grid.neighbours8(digit))
+            .any(|pos| PartNum::is_symbol(grid[pos]))
     }
-
-    Ok(file
-        .chars()
-        .enumerate()
-        .filter(|(_, c)| c == &'*')
-        .map(|(i, _)| {
-            let p = parts
-                .iter()
-                .filter(|part| part.is_adjacent(i).unwrap_or(false))
-                .collect::<Vec<_>>();
-            if p.len() == 2 {
-                p.iter()
-                    .map(|part| part.num().unwrap_or(0))
-                    .product::<i64>()
-            } else {
-                0
-            }
-        })
-        .sum::<i64>())
 }
 
-fn main() {}
+/// The engine schematic and the numbers found in it.
+#[derive(Debug, Clone)]
+pub struct Schematic {
+    pub grid: Grid<char>,
+    pub part_nums: Vec<PartNum>,
+}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl Solution for Schematic {
+    type Input = Schematic;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let grid = Grid::parse(DAY, input, "schematic", Some)?;
+
+        let mut part_nums = vec![];
+        for (y, row) in grid.rows().enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                let len = row[x..].iter().take_while(|c| c.is_ascii_digit()).count();
+                if len > 0 {
+                    let digits = row[x..x + len].iter().collect::<String>();
+                    let num = digits
+                        .parse()
+                        .map_err(|_| ParseError::new(DAY, y + 1, x + 1, "part number", &digits))?;
+                    part_nums.push(PartNum {
+                        num,
+                        pos: (x, y),
+                        len,
+                    });
+                }
+                x += len.max(1);
+            }
+        }
 
-    #[test]
-    fn test_part1_ex() -> Result<()> {
-        assert_eq!(part1("day03_p1_ex")?, 4361);
-        Ok(())
+        Ok(Schematic { grid, part_nums })
     }
 
-    #[test]
-    fn test_part1_in() -> Result<()> {
-        assert_eq!(part1("day03_p1_in")?, 512794);
-        Ok(())
+    fn part1(input: &Self::Input) -> Result<i64> {
+        Ok(input
+            .part_nums
+            .iter()
+            .inspect(|p| trace!("{}:{}", p.num, p.is_valid(&input.grid)))
+            .filter(|p| p.is_valid(&input.grid))
+            .map(|p| p.num)
+            .sum())
     }
 
-    #[test]
-    fn test_part2_ex() -> Result<()> {
-        assert_eq!(part2("day03_p1_ex")?, 467835);
-        Ok(())
+    fn part2(input: &Self::Input) -> Result<i64> {
+        Ok(input
+            .grid
+            .iter()
+            .filter(|(_, c)| **c == '*')
+            .map(|(pos, _)| {
+                let p = input
+                    .part_nums
+                    .iter()
+                    .filter(|part| part.is_adjacent(pos))
+                    .collect::<Vec<_>>();
+                if p.len() == 2 {
+                    p.iter().map(|part| part.num).product::<i64>()
+                } else {
+                    0
+                }
+            })
+            .sum::<i64>())
     }
+}
 
-    #[test]
-    fn test_part2_in() -> Result<()> {
-        assert_eq!(part2("day03_p1_in")?, 67779080);
-        Ok(())
-    }
+pub fn solve_part1(input: &str) -> Result<i64> {
+    Schematic::solve_part1(input)
+}
+
+pub fn solve_part2(input: &str) -> Result<i64> {
+    Schematic::solve_part2(input)
 }
@@ -11,55 +11,22 @@ use indicatif::{ProgressBar, ProgressStyle};
 
 use std::collections::{HashMap, HashSet, VecDeque};
 
+use log::trace;
+
+use crate::error::{parse_int, ParseError};
+use crate::Solution;
+
+const DAY: u32 = 4;
+
 #[derive(Parser)]
 #[grammar = "day04.pest"]
 struct CardParser;
 
-fn part1(input: &'static str) -> Result<i64> {
-    let pile_points = std::fs::read_to_string(input)
-        .expect("Could not read file")
-        .split("\n")
-        .filter(|line| !line.is_empty())
-        .inspect(|line| println!("line: {:?}", line))
-        .map(|line| {
-            let mut card = CardParser::parse(Rule::card, line)
-                .unwrap_or_else(|e| panic!("{}", e))
-                .next()
-                .unwrap()
-                .into_inner();
-
-            let _num_card = card.next().unwrap().as_str().parse::<i64>().unwrap();
-            let winning_numbers = card
-                .next()
-                .unwrap()
-                .into_inner()
-                .map(|inner_pair| inner_pair.as_str().parse::<i64>().unwrap())
-                .collect::<HashSet<_>>();
-            let have_numbers = card
-                .next()
-                .unwrap()
-                .into_inner()
-                .map(|inner_pair| inner_pair.as_str().parse::<i64>().unwrap())
-                .collect::<HashSet<_>>();
-
-            let num_winning_numbers =
-                winning_numbers.intersection(&have_numbers).count() as i64 - 1;
-            if num_winning_numbers >= 0 {
-                2i64.pow(num_winning_numbers as u32)
-            } else {
-                0
-            }
-        })
-        .sum::<i64>();
-
-    Ok(pile_points)
-}
-
 #[derive(Debug, Clone, Eq)]
-struct Card {
-    num_card: i64,
-    winning_numbers: HashSet<i64>,
-    have_numbers: HashSet<i64>,
+pub struct Card {
+    pub num_card: i64,
+    pub winning_numbers: HashSet<i64>,
+    pub have_numbers: HashSet<i64>,
 }
 
 impl PartialEq for Card {
@@ -70,7 +37,7 @@ impl PartialEq for Card {
 
 impl PartialOrd for Card {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.num_card.partial_cmp(&other.num_card)
+        Some(self.cmp(other))
     }
 }
 
@@ -81,12 +48,12 @@ impl Ord for Card {
 }
 
 impl Card {
-    fn num_winning_numbers(&self) -> usize {
+    pub fn num_winning_numbers(&self) -> usize {
         self.winning_numbers
             .intersection(&self.have_numbers)
             .count()
     }
-    fn eval(&self, cards: &mut VecDeque<CardPile>) -> () {
+    pub fn eval(&self, cards: &mut VecDeque<CardPile>) {
         let num_winning_numbers = self.num_winning_numbers();
 
         let idx_init = self.num_card as usize;
@@ -107,67 +74,110 @@ impl Display for Card {
 }
 
 #[derive(Debug, Clone)]
-struct CardPile {
-    cards: Vec<Card>,
+pub struct CardPile {
+    pub cards: Vec<Card>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Scratchcards {
+    pub cards: Vec<Card>,
 }
 
-fn part2(input: &'static str) -> Result<i64> {
-    let mut cardpiles = std::fs::read_to_string(input)
-        .expect("Could not read file")
-        .split("\n")
-        .filter(|line| !line.is_empty())
-        .inspect(|line| println!("line: {:?}", line))
-        .map(|line| {
-            let mut card = CardParser::parse(Rule::card, line)
-                .unwrap_or_else(|e| panic!("{}", e))
-                .next()
-                .unwrap()
-                .into_inner();
-
-            let num_card = card.next().unwrap().as_str().parse::<i64>().unwrap();
-            let winning_numbers = card
-                .next()
-                .unwrap()
-                .into_inner()
-                .map(|inner_pair| inner_pair.as_str().parse::<i64>().unwrap())
-                .collect::<HashSet<_>>();
-            let have_numbers = card
-                .next()
-                .unwrap()
-                .into_inner()
-                .map(|inner_pair| inner_pair.as_str().parse::<i64>().unwrap())
-                .collect::<HashSet<_>>();
-
-            Card {
-                num_card,
-                winning_numbers,
-                have_numbers,
-            }
-        })
-        .map(|card| CardPile { cards: vec![card] })
-        .collect::<VecDeque<_>>();
-
-    let mut overall_num_cards = 0;
-    let mut cardpile_idx = 0;
-    while cardpile_idx < cardpiles.len() {
-        let cardpile = cardpiles[cardpile_idx].clone();
-        overall_num_cards += cardpile.cards.len();
-        println!("cardpile len: {}", cardpile.cards.len());
-        cardpile
+impl Solution for Scratchcards {
+    type Input = Scratchcards;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let cards = input
+            .split('\n')
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .inspect(|(_, line)| trace!("line: {:?}", line))
+            .map(|(line_idx, line)| {
+                let parse_card = || -> Result<Card, ParseError> {
+                    let card = CardParser::parse(Rule::card, line)
+                        .map_err(|e| ParseError::from_pest(DAY, e))?
+                        .next()
+                        .ok_or_else(|| ParseError::new(DAY, 1, 1, "card", line))?;
+                    let missing = |expected| ParseError::from_pair(DAY, &card, expected);
+                    let mut fields = card.clone().into_inner();
+
+                    let num_card =
+                        parse_int(DAY, &fields.next().ok_or_else(|| missing("card number"))?)?;
+                    let winning_numbers = fields
+                        .next()
+                        .ok_or_else(|| missing("winning numbers"))?
+                        .into_inner()
+                        .map(|inner_pair| parse_int(DAY, &inner_pair))
+                        .collect::<Result<HashSet<_>, _>>()?;
+                    let have_numbers = fields
+                        .next()
+                        .ok_or_else(|| missing("numbers you have"))?
+                        .into_inner()
+                        .map(|inner_pair| parse_int(DAY, &inner_pair))
+                        .collect::<Result<HashSet<_>, _>>()?;
+
+                    Ok(Card {
+                        num_card,
+                        winning_numbers,
+                        have_numbers,
+                    })
+                };
+                parse_card().map_err(|e| e.on_line(line_idx + 1))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Scratchcards { cards })
+    }
+
+    fn part1(input: &Self::Input) -> Result<i64> {
+        let pile_points = input
+            .cards
+            .iter()
+            .map(|card| {
+                let num_winning_numbers = card.num_winning_numbers() as i64 - 1;
+                if num_winning_numbers >= 0 {
+                    2i64.pow(num_winning_numbers as u32)
+                } else {
+                    0
+                }
+            })
+            .sum::<i64>();
+
+        Ok(pile_points)
+    }
+
+    fn part2(input: &Self::Input) -> Result<i64> {
+        let mut cardpiles = input
             .cards
             .iter()
-            .for_each(|card| card.eval(&mut cardpiles));
+            .cloned()
+            .map(|card| CardPile { cards: vec![card] })
+            .collect::<VecDeque<_>>();
+
+        let mut overall_num_cards = 0;
+        let mut cardpile_idx = 0;
+        while cardpile_idx < cardpiles.len() {
+            let cardpile = cardpiles[cardpile_idx].clone();
+            overall_num_cards += cardpile.cards.len();
+            trace!("cardpile len: {}", cardpile.cards.len());
+            cardpile
+                .cards
+                .iter()
+                .for_each(|card| card.eval(&mut cardpiles));
+
+            cardpile_idx += 1;
+        }
 
-        cardpile_idx += 1;
+        Ok(overall_num_cards as i64)
     }
+}
 
-    Ok(overall_num_cards as i64)
+pub fn solve_part1(input: &str) -> Result<i64> {
+    Scratchcards::solve_part1(input)
 }
 
-fn main() -> Result<()> {
-    println!("Part 1: {}", part1("day04_p1_in")?);
-    println!("Part 2: {}", part2("day04_p1_in")?);
-    Ok(())
+pub fn solve_part2(input: &str) -> Result<i64> {
+    Scratchcards::solve_part2(input)
 }
 
 #[cfg(test)]
@@ -180,28 +190,4 @@ mod tests {
         assert_eq!(2, 2i64.pow(1));
         assert_eq!(4, 2i64.pow(2));
     }
-
-    #[test]
-    fn test_part1_ex() -> Result<()> {
-        assert_eq!(part1("day04_p1_ex")?, 13);
-        Ok(())
-    }
-
-    #[test]
-    fn test_part1_in() -> Result<()> {
-        assert_eq!(part1("day04_p1_in")?, 25174);
-        Ok(())
-    }
-
-    #[test]
-    fn test_part2_ex() -> Result<()> {
-        assert_eq!(part2("day04_p1_ex")?, 30);
-        Ok(())
-    }
-
-    #[test]
-    fn test_part2_in() -> Result<()> {
-        assert_eq!(part2("day04_p1_in")?, 6420979);
-        Ok(())
-    }
 }
@@ -1,11 +1,12 @@
-#![allow(dead_code)]
 #![allow(unused_imports)]
 
 use contracts::*;
 
 use std::fmt::{Display, Formatter};
+use std::ops::Range;
 
 use anyhow::{anyhow, Result};
+use pest::iterators::Pair;
 use pest::Parser;
 use pest_derive::Parser;
 
@@ -15,402 +16,500 @@ use indicatif::{ProgressBar, ProgressStyle};
 
 use std::collections::{HashMap, HashSet, VecDeque};
 
+use log::{debug, trace};
+
+use crate::error::{parse_int, ParseError};
+use crate::graph::{Graph, NodeId};
+use crate::interval::IntervalSet;
+use crate::Solution;
+
+const DAY: u32 = 5;
+
 #[derive(Parser)]
 #[grammar = "day05.pest"]
 struct AlmanacParser;
 
-#[derive(Debug)]
-struct Map {
-    mappings: Vec<(i64, i64, i64)>,
+/// A piecewise offset function: values in a piece's source range move by its offset,
+/// everything else maps to itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Map {
+    /// Categories from a header like `seed-to-soil`, empty for the identity.
+    pub from: String,
+    pub to: String,
+    /// `(dest, src, len)` as written in the almanac.
+    pub mappings: Vec<(i64, i64, i64)>,
+    /// Disjoint source ranges sorted by start, each with its offset.
+    pieces: Vec<(Range<i64>, i64)>,
 }
 
-struct FromTo {
-    from: Interval,
-    to: Interval,
-}
+impl Map {
+    /// Where mappings overlap, the one listed first wins.
+    pub fn new(
+        from: impl Into<String>,
+        to: impl Into<String>,
+        mappings: Vec<(i64, i64, i64)>,
+    ) -> Self {
+        let mut covered = IntervalSet::new();
+        let mut pieces = vec![];
+        for &(to, from, len) in &mappings {
+            let source = from..from + len;
+            let free = IntervalSet::from(source.clone()).difference(&covered);
+            pieces.extend(free.iter().map(|range| (range, to - from)));
+            covered.insert(source);
+        }
+        pieces.sort_by_key(|(range, _)| range.start);
 
-impl FromTo {
-    fn offset(&self) -> i64 {
-        self.to.start() - self.from.start()
+        Self {
+            from: from.into(),
+            to: to.into(),
+            mappings,
+            pieces,
+        }
     }
-}
 
-impl Map {
-    fn transform(&self, val: i64) -> i64 {
-        for mapping in &self.mappings {
-            if (mapping.1..=mapping.1 + mapping.2).contains(&val) {
-                return val + mapping.0 - mapping.1;
+    /// The map that leaves every value where it is.
+    pub fn identity() -> Self {
+        Self::default()
+    }
+
+    fn from_pieces(from: String, to: String, mut pieces: Vec<(Range<i64>, i64)>) -> Self {
+        pieces.retain(|(range, offset)| !range.is_empty() && *offset != 0);
+        pieces.sort_by_key(|(range, _)| range.start);
+        let mappings = pieces
+            .iter()
+            .map(|(range, offset)| (range.start + offset, range.start, range.end - range.start))
+            .collect();
+
+        Self {
+            from,
+            to,
+            mappings,
+            pieces,
+        }
+    }
+
+    /// The source range of each piece and how far it moves its values.
+    pub fn pieces(&self) -> &[(Range<i64>, i64)] {
+        &self.pieces
+    }
+
+    pub fn apply(&self, val: i64) -> i64 {
+        let i = self.pieces.partition_point(|(range, _)| range.start <= val);
+        match i.checked_sub(1).map(|i| &self.pieces[i]) {
+            Some((range, offset)) if range.contains(&val) => val + offset,
+            _ => val,
+        }
+    }
+
+    /// Maps a whole set of values, a range at a time.
+    pub fn image(&self, values: &IntervalSet) -> IntervalSet {
+        let mut mapped = IntervalSet::new();
+        let mut unmapped = values.clone();
+        for (source, offset) in &self.pieces {
+            for range in values.within(source.clone()) {
+                trace!("{:?} moves by {}", range, offset);
+                mapped.insert(range.start + offset..range.end + offset);
             }
+            unmapped.remove(source.clone());
         }
 
-        val
+        mapped.union(&unmapped)
     }
 
-    fn mappings_as_fromtos(&self) -> Vec<FromTo> {
-        self.mappings
+    /// The offset at `val` and where the run of values sharing it ends.
+    fn run_at(&self, val: i64) -> (i64, i64) {
+        let i = self.pieces.partition_point(|(range, _)| range.start <= val);
+        match i.checked_sub(1).map(|i| &self.pieces[i]) {
+            Some((range, offset)) if range.contains(&val) => (*offset, range.end),
+            _ => (
+                0,
+                self.pieces
+                    .get(i)
+                    .map_or(i64::MAX, |(range, _)| range.start),
+            ),
+        }
+    }
+
+    /// Every value that maps into `values`.
+    pub fn preimage(&self, values: &IntervalSet) -> IntervalSet {
+        let mut preimage = IntervalSet::new();
+        let mut sources = IntervalSet::new();
+        for (source, offset) in &self.pieces {
+            let image = source.start + offset..source.end + offset;
+            for range in values.within(image) {
+                preimage.insert(range.start - offset..range.end - offset);
+            }
+            sources.insert(source.clone());
+        }
+
+        preimage.union(&values.difference(&sources))
+    }
+
+    /// The map undoing this one, if it is one-to-one and so every value has exactly
+    /// one preimage.
+    pub fn inverse(&self) -> Option<Map> {
+        let sources = self
+            .pieces
             .iter()
-            .map(|mapping| FromTo {
-                from: Interval::new(mapping.1..mapping.1 + mapping.2),
-                to: Interval::new(mapping.0..mapping.0 + mapping.2),
-            })
-            .collect::<Vec<_>>()
+            .map(|(range, _)| range.clone())
+            .collect::<IntervalSet>();
+        let images = self
+            .pieces
+            .iter()
+            .map(|(range, offset)| range.start + offset..range.end + offset)
+            .collect::<IntervalSet>();
+        // Pieces moved onto each other, or onto values left alone, would have their
+        // values land on the same spot.
+        if images != sources {
+            return None;
+        }
+
+        Some(Map::from_pieces(
+            self.to.clone(),
+            self.from.clone(),
+            self.pieces
+                .iter()
+                .map(|(range, offset)| (range.start + offset..range.end + offset, -offset))
+                .collect(),
+        ))
     }
 
-    fn transform_interval(&self, location: &Interval) -> Vec<Interval> {
-        let mappings = self.mappings_as_fromtos();
-        let split = location.split_on_ranges(
-            &mappings
-                .into_iter()
-                .map(|mapping| {
-                    let offset = mapping.to.start() - mapping.from.start();
-                    (mapping.from, offset)
-                })
-                .collect::<Vec<(Interval, i64)>>(),
-        );
-        split
+    /// This map followed by `next`, as a single map.
+    pub fn then(&self, next: &Map) -> Map {
+        let sources = self
+            .pieces
+            .iter()
+            .map(|(range, _)| range.clone())
+            .collect::<IntervalSet>();
+        let mut pieces = vec![];
+
+        // Values this map moves, split by where `next` moves them on to.
+        for (source, offset) in &self.pieces {
+            let moved = IntervalSet::from(source.start + offset..source.end + offset);
+            let mut rest = moved.clone();
+            for (next_source, next_offset) in &next.pieces {
+                for range in moved.within(next_source.clone()) {
+                    pieces.push((
+                        range.start - offset..range.end - offset,
+                        offset + next_offset,
+                    ));
+                }
+                rest.remove(next_source.clone());
+            }
+            pieces.extend(
+                rest.iter()
+                    .map(|range| (range.start - offset..range.end - offset, *offset)),
+            );
+        }
+        // Values this map leaves alone, moved by `next` only.
+        for (next_source, next_offset) in &next.pieces {
+            let untouched = IntervalSet::from(next_source.clone()).difference(&sources);
+            pieces.extend(untouched.iter().map(|range| (range, *next_offset)));
+        }
+
+        let from = match self.from.is_empty() {
+            true => next.from.clone(),
+            false => self.from.clone(),
+        };
+        let to = match next.to.is_empty() {
+            true => self.to.clone(),
+            false => next.to.clone(),
+        };
+        Map::from_pieces(from, to, pieces)
     }
 }
 
-fn part1(input: &'static str) -> Result<i64> {
-    let file_contents = std::fs::read_to_string(input).expect("Could not read file");
-    let mut almanac = AlmanacParser::parse(Rule::almanac, file_contents.as_str())
-        .unwrap_or_else(|e| panic!("{}", e))
-        .next()
-        .unwrap()
-        .into_inner();
+/// Following one of the almanac's maps, or going back through it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Forward(usize),
+    Backward(usize),
+}
 
-    let seeds = almanac
-        .next()
-        .unwrap()
-        .into_inner()
-        .map(|seed| seed.as_str().parse::<i64>().unwrap())
-        .collect::<Vec<_>>();
-    let maps = almanac.map(|pair| Map {
-        mappings: pair
-            .into_inner()
-            .map(|p| p.as_str().parse::<i64>().unwrap())
-            .collect::<Vec<_>>()
-            .chunks(3)
-            .map(|slice| (slice[0], slice[1], slice[2]))
-            .collect::<Vec<_>>(),
-    });
-    let mut locations: Vec<i64> = seeds;
-    println!("{:?}", locations);
-    if maps.len() != 7 {
-        return Err(anyhow!("Wrong number of maps: {}", maps.len()));
-    }
-    maps.for_each(|map| {
-        println!("{:?}", map);
-        println!(
-            "{:?}",
-            locations
-                .iter_mut()
-                .map(|seed| {
-                    *seed = map.transform(*seed);
-                    seed
-                })
-                .collect::<Vec<_>>()
-        );
-    });
-    Ok(*locations.iter().min().unwrap())
+#[derive(Debug, Clone)]
+pub struct Almanac {
+    pub seeds: Vec<i64>,
+    pub maps: Vec<Map>,
+    /// Categories linked by the maps between them, forwards before backwards.
+    pub categories: Graph<String, Step>,
 }
 
-fn part2_old(input: &'static str) -> Result<i64> {
-    let file_contents = std::fs::read_to_string(input).expect("Could not read file");
-    let mut almanac = AlmanacParser::parse(Rule::almanac, file_contents.as_str())
-        .unwrap_or_else(|e| panic!("{}", e))
-        .next()
-        .unwrap()
-        .into_inner();
+impl Almanac {
+    pub fn new(seeds: Vec<i64>, maps: Vec<Map>) -> Self {
+        let mut categories = Graph::new();
+        for (i, map) in maps.iter().enumerate() {
+            let (from, to) = (
+                categories.add_node(map.from.clone()),
+                categories.add_node(map.to.clone()),
+            );
+            categories.add_edge(from, to, Step::Forward(i));
+        }
+        for (i, map) in maps.iter().enumerate() {
+            let (from, to) = (
+                categories.add_node(map.from.clone()),
+                categories.add_node(map.to.clone()),
+            );
+            categories.add_edge(to, from, Step::Backward(i));
+        }
 
-    let seeds = almanac
-        .next()
-        .unwrap()
-        .into_inner()
-        .map(|seed| seed.as_str().parse::<i64>().unwrap())
-        .collect::<Vec<_>>()
-        .chunks(2)
-        .map(|slice| (slice[0]..slice[0] + slice[1]).collect::<Vec<_>>())
-        .flatten()
-        .collect::<Vec<_>>();
-    // println!("{:?}", seeds);
-    let maps = almanac.map(|pair| Map {
-        mappings: pair
-            .into_inner()
-            .map(|p| p.as_str().parse::<i64>().unwrap())
-            .collect::<Vec<_>>()
-            .chunks(3)
-            .map(|slice| (slice[0], slice[1], slice[2]))
-            .collect::<Vec<_>>(),
-    });
-    let mut locations: Vec<i64> = seeds;
-    // println!("{:?}", locations);
-    if maps.len() != 7 {
-        return Err(anyhow!("Wrong number of maps: {}", maps.len()));
-    }
-    maps.for_each(|map| {
-        // println!("{:?}", map);
-        locations.par_iter_mut().for_each(|seed| {
-            *seed = map.transform(*seed);
-        });
-    });
-    Ok(*locations.iter().min().unwrap())
-}
+        Self {
+            seeds,
+            maps,
+            categories,
+        }
+    }
 
-#[derive(Debug, Eq, PartialEq, Clone)]
-struct Interval {
-    range: std::ops::Range<i64>,
-}
-impl Display for Interval {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[{}, {}]", self.start(), self.end() - 1)
+    /// The maps along the shortest route from one category to another, composed
+    /// into one. Only without a route of forward maps are maps followed backwards,
+    /// and then only those that invert.
+    pub fn converter(&self, from: &str, to: &str) -> Result<Map> {
+        let category = |name: &str| {
+            self.categories
+                .id(&name.to_string())
+                .ok_or_else(|| anyhow!("Unknown category `{}`", name))
+        };
+        let (from_id, to_id) = (category(from)?, category(to)?);
+
+        let forward = |step: &Step| matches!(step, Step::Forward(_));
+        if let Some(route) = self.categories.shortest_path_by(from_id, to_id, forward) {
+            return self.compose(&route, |_| None);
+        }
+
+        let inverses = self.maps.iter().map(Map::inverse).collect::<Vec<_>>();
+        let invertible = |step: &Step| match *step {
+            Step::Forward(_) => true,
+            Step::Backward(i) => inverses[i].is_some(),
+        };
+        let route = self
+            .categories
+            .shortest_path_by(from_id, to_id, invertible)
+            .ok_or_else(|| anyhow!("No maps lead from {} to {}", from, to))?;
+        self.compose(&route, |i| inverses[i].as_ref())
     }
-}
-impl PartialOrd for Interval {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.range.start.partial_cmp(&other.range.start)
+
+    /// The maps between consecutive categories of `route`, preferring forward maps,
+    /// with `inverse` giving the map to follow backwards in place of map `i`.
+    fn compose<'a>(
+        &'a self,
+        route: &[NodeId],
+        inverse: impl Fn(usize) -> Option<&'a Map>,
+    ) -> Result<Map> {
+        let mut converter = Map::identity();
+        for pair in route.windows(2) {
+            let map = self
+                .categories
+                .edges(pair[0])
+                .iter()
+                .filter(|(next, _)| *next == pair[1])
+                .find_map(|&(_, step)| match step {
+                    Step::Forward(i) => Some(&self.maps[i]),
+                    Step::Backward(i) => inverse(i),
+                })
+                .ok_or_else(|| anyhow!("Route through a missing map"))?;
+            trace!("{} to {}", map.from, map.to);
+            converter = converter.then(map);
+        }
+        Ok(converter)
     }
-}
-impl Ord for Interval {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.range.start.cmp(&other.range.start)
+
+    /// What `value` of category `from` corresponds to in category `to`.
+    pub fn convert(&self, from: &str, value: i64, to: &str) -> Result<i64> {
+        Ok(self.converter(from, to)?.apply(value))
     }
-}
-impl Interval {
-    fn contains(&self, val: i64) -> bool {
-        self.range.contains(&val)
+
+    pub fn seed_to_location(&self) -> Result<Map> {
+        self.converter("seed", "location")
     }
-    #[requires(range.start < range.end)]
-    fn new(range: std::ops::Range<i64>) -> Self {
-        Self { range }
+
+    /// The seed each location comes from, if the maps are one-to-one.
+    pub fn location_to_seed(&self) -> Result<Map> {
+        self.converter("location", "seed")
     }
-    fn overlaps_both_sides_of(&self, other: &Self) -> bool {
-        self.range.start < other.range.start && self.range.end > other.range.end
+
+    /// Part 2 the other way around: the lowest location with a seed in the seed
+    /// ranges, scanning upwards from 0 a run of equally offset locations at a time.
+    pub fn lowest_location(&self) -> Result<i64> {
+        let location_to_seed = self.location_to_seed()?;
+        let seeds = self.seed_ranges()?;
+        let mut location = 0;
+        loop {
+            let (offset, end) = location_to_seed.run_at(location);
+            let run = location + offset..end.saturating_add(offset);
+            if let Some(seed) = seeds.within(run).next() {
+                return Ok(seed.start - offset);
+            }
+            if end == i64::MAX {
+                return Err(anyhow!("No seeds to locate"));
+            }
+            location = end;
+        }
     }
-    fn overlaps_left_of(&self, other: &Self) -> bool {
-        self.range.start < other.range.start
-            && self.range.end <= other.range.end
-            && self.range.end > other.range.start
+
+    /// The seeds line read as `(start, length)` pairs.
+    pub fn seed_ranges(&self) -> Result<IntervalSet> {
+        let pairs = self.seeds.chunks_exact(2);
+        if let [start] = pairs.remainder() {
+            return Err(anyhow!("Seed {} has no range length", start));
+        }
+        pairs
+            .map(|pair| match (pair[0], pair[1]) {
+                (start, len) if len < 0 => Err(anyhow!("Seed {} has length {}", start, len)),
+                (start, len) => start
+                    .checked_add(len)
+                    .map(|end| start..end)
+                    .ok_or_else(|| anyhow!("Seed range {}..+{} is too long", start, len)),
+            })
+            .collect()
     }
-    fn overlaps_right_of(&self, other: &Self) -> bool {
-        self.range.start >= other.range.start
-            && self.range.end > other.range.end
-            && self.range.start < other.range.end
+}
+
+/// Reads one map, rejecting empty or overlapping source ranges and a header already
+/// given by one of the `previous` maps.
+fn parse_map(pair: Pair<'_, Rule>, previous: &[Map]) -> Result<Map, ParseError> {
+    let mut inner = pair.clone().into_inner();
+    let name = inner
+        .next()
+        .ok_or_else(|| ParseError::from_pair(DAY, &pair, "map name"))?;
+    let (from, to) = name
+        .as_str()
+        .split_once("-to-")
+        .ok_or_else(|| ParseError::from_pair(DAY, &name, "map name like `seed-to-soil`"))?;
+    if previous.iter().any(|map| map.from == from && map.to == to) {
+        return Err(ParseError::from_pair(
+            DAY,
+            &name,
+            format!("only one `{}` map", name.as_str()),
+        ));
     }
-    fn is_contained(&self, other: &Self) -> bool {
-        self.range.start >= other.range.start && self.range.end <= other.range.end
+
+    let nums = inner.collect::<Vec<_>>();
+    if nums.len() % 3 != 0 {
+        return Err(ParseError::from_pair(DAY, &pair, "three numbers per range"));
     }
-    fn offset_by(&self, offset: &i64) -> Self {
-        Self {
-            range: (self.range.start + offset)..(self.range.end + offset),
+    let mut ranges = vec![];
+    for range in nums.chunks(3) {
+        let (to, from, len) = (
+            parse_int(DAY, &range[0])?,
+            parse_int(DAY, &range[1])?,
+            parse_int(DAY, &range[2])?,
+        );
+        if len == 0 {
+            return Err(ParseError::from_pair(
+                DAY,
+                &range[2],
+                "range length above 0",
+            ));
         }
+        if from.checked_add(len).is_none() || to.checked_add(len).is_none() {
+            return Err(ParseError::from_pair(
+                DAY,
+                &range[2],
+                format!("range length up to {}", i64::MAX - from.max(to)),
+            ));
+        }
+        ranges.push(((to, from, len), &range[1]));
     }
-    fn split_on_ranges(&self, ranges_and_offset: &[(Interval, i64)]) -> Vec<Interval> {
-        let mut ret_split_intervals = vec![self.clone()];
-        let mut idx = 0i64;
 
-        println!(
-            "vals pre split = {}",
-            ret_split_intervals
-                .iter()
-                .map(|old| old.to_string())
-                .collect::<Vec<String>>()
-                .join("; ")
-        );
+    let mut by_source = ranges.iter().collect::<Vec<_>>();
+    by_source.sort_by_key(|((_, from, _), _)| *from);
+    for pair in by_source.windows(2) {
+        let (((_, from, len), first), ((_, next, _), second)) = (pair[0], pair[1]);
+        if *next < from + len {
+            let (line, _) = first.as_span().start_pos().line_col();
+            return Err(ParseError::from_pair(
+                DAY,
+                second,
+                format!(
+                    "source range clear of {}..{} on line {}",
+                    from,
+                    from + len,
+                    line
+                ),
+            ));
+        }
+    }
 
-        while idx < ret_split_intervals.len() as i64 {
-            for (range, _) in ranges_and_offset {
-                let this = &mut ret_split_intervals[idx as usize];
-                let start = this.range.start.clone();
-                let end = this.range.end.clone();
-                if this.range.start == 1972667147 {
-                    println!("checking this: {}, range: {}", this, range);
-                }
-                match (this.clone(), range) {
-                    (_, _) if this.overlaps_both_sides_of(range) => {
-                        println!("range {} overlaps both sides of {}", this, range);
-                        // Split into 3
-                        let removed = ret_split_intervals.remove(idx as usize);
-                        println!("removed interval: {}", removed);
-                        ret_split_intervals.push(Interval::new(start..range.start()));
-                        println!("new interval: {}", ret_split_intervals.last().unwrap());
-                        ret_split_intervals.push(range.clone());
-                        println!("new interval: {}", ret_split_intervals.last().unwrap());
-                        ret_split_intervals.push(Interval::new(range.end()..end));
-                        println!("new interval: {}", ret_split_intervals.last().unwrap());
-
-                        idx = -1;
-                        break;
-                    }
-                    (_, _) if this.overlaps_left_of(range) => {
-                        println!("range {} overlaps left of {}", this, range);
-                        // Split into 2
-                        let removed = ret_split_intervals.remove(idx as usize);
-                        println!("removed interval: {}", removed);
-                        ret_split_intervals.push(Interval::new(start..range.start()));
-                        println!("new interval: {}", ret_split_intervals.last().unwrap());
-                        ret_split_intervals.push(Interval::new(range.start()..end));
-                        println!("new interval: {}", ret_split_intervals.last().unwrap());
-
-                        idx = -1;
-                        break;
-                    }
-                    (_, _) if this.overlaps_right_of(range) => {
-                        println!("range {} overlaps right of {}", this, range);
-                        // Split into 2
-                        let removed = ret_split_intervals.remove(idx as usize);
-                        println!("removed interval: {}", removed);
-                        ret_split_intervals.push(Interval::new(start..range.end()));
-                        println!("new interval: {}", ret_split_intervals.last().unwrap());
-                        ret_split_intervals.push(Interval::new(range.end()..end));
-                        println!("new interval: {}", ret_split_intervals.last().unwrap());
-
-                        idx = -1;
-                        break;
-                    }
-                    _ => {}
-                }
-            }
+    Ok(Map::new(
+        from,
+        to,
+        ranges.into_iter().map(|(mapping, _)| mapping).collect(),
+    ))
+}
 
-            idx += 1;
-        }
+impl Solution for Almanac {
+    type Input = Almanac;
 
-        let old = ret_split_intervals
-            .iter()
-            .cloned()
-            .collect::<Vec<Interval>>();
-
-        println!(
-            "vals post split = {}",
-            old.iter()
-                .map(|old| old.to_string())
-                .collect::<Vec<String>>()
-                .join("; ")
-        );
+    fn parse(input: &str) -> Result<Self::Input> {
+        let mut almanac = AlmanacParser::parse(Rule::almanac, input)
+            .map_err(|e| ParseError::from_pest(DAY, e))?
+            .next()
+            .ok_or_else(|| ParseError::at_offset(DAY, input, 0, "almanac"))?
+            .into_inner();
 
-        ret_split_intervals.iter_mut().for_each(|interval| {
-            for (range, offset) in ranges_and_offset {
-                match (interval.clone(), range) {
-                    (t, r) if t.overlaps_both_sides_of(r) => {
-                        panic!("range {} overlaps both sides of {}", t, r);
-                    }
-                    (t, r) if t.overlaps_left_of(r) => {
-                        panic!("range {} overlaps left of {}", t, r);
-                    }
-                    (t, r) if t.overlaps_right_of(r) => {
-                        panic!("range {} overlaps right of {}", t, r);
-                    }
-                    _ => {}
-                }
-            }
-        });
-        ret_split_intervals
-            .iter_mut()
-            .for_each(|interval: &mut Interval| {
-                for (range, offset) in ranges_and_offset {
-                    if interval.is_contained(range) {
-                        println!(
-                            "range {} is contained in {} offset: {}",
-                            interval, range, offset
-                        );
-                        *interval = interval.offset_by(offset);
-                        break;
-                    }
-                }
-            });
+        let seeds = almanac
+            .next()
+            .ok_or_else(|| ParseError::at_offset(DAY, input, 0, "seeds"))?
+            .into_inner()
+            .map(|seed| parse_int(DAY, &seed))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (mut maps, mut headers) = (vec![], vec![]);
+        for pair in almanac {
+            headers.push(pair.clone().into_inner().next());
+            maps.push(parse_map(pair, &maps)?);
+        }
 
-        if old.len() != ret_split_intervals.len() {
-            panic!("old.len() != intervals.len()");
+        // Blame the first map that no chain of maps links to the seeds.
+        let almanac = Almanac::new(seeds, maps);
+        let linked = almanac
+            .categories
+            .id(&"seed".to_string())
+            .map(|seed| almanac.categories.bfs(seed))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(id, _)| almanac.categories.label(id).as_str())
+            .collect::<HashSet<_>>();
+        let unlinked = almanac
+            .maps
+            .iter()
+            .position(|map| !linked.contains(map.from.as_str()));
+        if let Some(header) = unlinked.and_then(|i| headers[i].as_ref()) {
+            return Err(ParseError::from_pair(DAY, header, "a map linked to `seed`").into());
         }
-        old.iter()
-            .zip(ret_split_intervals.iter())
-            .for_each(|(old, new)| {
-                println!("{} => {}", old, new);
-            });
 
-        ret_split_intervals
+        Ok(almanac)
     }
 
-    fn start(&self) -> i64 {
-        self.range.start
+    fn part1(input: &Self::Input) -> Result<i64> {
+        let seed_to_location = input.seed_to_location()?;
+        debug!(
+            "{} pieces in the composed map",
+            seed_to_location.pieces().len()
+        );
+        input
+            .seeds
+            .iter()
+            .map(|&seed| seed_to_location.apply(seed))
+            .min()
+            .ok_or_else(|| anyhow!("No seeds to locate"))
     }
-    fn end(&self) -> i64 {
-        // Make it inclusive
-        self.range.end
+
+    fn part2(input: &Self::Input) -> Result<i64> {
+        let seeds = input.seed_ranges()?;
+        debug!("seeds: {}", seeds);
+        input
+            .seed_to_location()?
+            .image(&seeds)
+            .min()
+            .ok_or_else(|| anyhow!("No seeds to locate"))
     }
 }
-fn part2(input: &'static str) -> Result<i64> {
-    let file_contents = std::fs::read_to_string(input).expect("Could not read file");
-    let mut almanac = AlmanacParser::parse(Rule::almanac, file_contents.as_str())
-        .unwrap_or_else(|e| panic!("{}", e))
-        .next()
-        .unwrap()
-        .into_inner();
-
-    let seeds = almanac
-        .next()
-        .unwrap()
-        .into_inner()
-        .map(|seed| seed.as_str().parse::<i64>().unwrap())
-        .collect::<Vec<_>>()
-        .chunks(2)
-        .map(|slice| Interval::new(slice[0]..slice[0] + slice[1]))
-        .collect::<Vec<Interval>>();
-    // println!("{:?}", seeds);
-    let maps = almanac
-        .map(|pair| Map {
-            mappings: pair
-                .into_inner()
-                .map(|p| p.as_str().parse::<i64>().unwrap())
-                .collect::<Vec<_>>()
-                .chunks(3)
-                .map(|slice| (slice[0], slice[1], slice[2]))
-                .collect::<Vec<_>>(),
-        })
-        .collect::<Vec<_>>();
-    let mut locations: Vec<Interval> = seeds;
-    // println!("{:?}", locations);
-    if maps.len() != 7 {
-        return Err(anyhow!("Wrong number of maps: {}", maps.len()));
-    }
-    // println!("maps[0].fromto[0]: {}, {}", fromtos[0].from, fromtos[0].to);
-    // println!("maps[0].fromto[1]: {}, {}", fromtos[1].from, fromtos[1].to);
-    for location in &locations {
-        println!("{}", location);
-    }
-    println!("+++");
-    maps.iter().for_each(|map| {
-        let fromtos = map.mappings_as_fromtos();
-        for fromto in &fromtos {
-            println!(
-                "fromto: {}, {}, {}",
-                fromto.from,
-                fromto.to,
-                fromto.offset()
-            );
-        }
-
-        let mut new_locations = vec![];
-        let mut locations_iter = locations.iter();
-        while let Some(location) = locations_iter.next() {
-            let new_locals = map.transform_interval(location);
-            new_locations.extend(new_locals);
-        }
 
-        locations = new_locations;
-
-        // for location in &locations {
-        //     println!("{}", location);
-        // }
-        println!("+++");
-    });
-    Ok(locations.iter().min().unwrap().start())
+pub fn solve_part1(input: &str) -> Result<i64> {
+    Almanac::solve_part1(input)
 }
 
-fn main() -> Result<()> {
-    // println!("Part 1: {}", part1("day05_p1_in")?);
-    println!("Part 2: {}", part2_old("day05_p1_in")?);
-    Ok(())
+pub fn solve_part2(input: &str) -> Result<i64> {
+    Almanac::solve_part2(input)
 }
 
 #[cfg(test)]
@@ -419,51 +518,190 @@ mod tests {
 
     #[test]
     fn interval_test() -> Result<()> {
-        let interval = Interval::new(1972667147..2203381571);
-        assert_eq!(
-            interval.overlaps_left_of(&Interval::new(2032673361..2203381571)),
-            true
-        );
+        let seeds = IntervalSet::from(1972667147..2203381571);
+        assert!(seeds.overlaps(2032673361..2203381571));
+        assert!(!seeds.contains_range(1900000000..2032673361));
         Ok(())
     }
 
     #[test]
-    fn numbers_test() -> Result<()> {
-        assert_eq!(2378259165u32, 1972667147 + 405592018);
-        assert_eq!(1477976316u32, 1450194064 + 27782252);
-        assert_eq!(410212617u32, 348350443 + 61862174);
-        assert_eq!(4092364215u32, 3911195009 + 181169206);
-        assert_eq!(765648080u32, 626861593 + 138786487);
-        assert_eq!(3162265119u32, 2886966111 + 275299008);
-        assert_eq!(1303406955u32, 825403564 + 478003391);
-        assert_eq!(520687690u32, 514585599 + 6102091);
-        assert_eq!(2541511753u32, 2526020300 + 15491453);
-        assert_eq!(3757205391u32, 3211013652 + 546191739);
+    fn image_test() {
+        let map = Map::new("seed", "soil", vec![(50, 98, 2), (52, 50, 48)]);
+        let mapped = map.image(&IntervalSet::from(45..100));
+        assert_eq!(mapped, IntervalSet::from(45..100));
+        let mapped = map.image(&IntervalSet::from(96..100));
+        assert_eq!(mapped.iter().collect::<Vec<_>>(), [50..52, 98..100]);
+    }
+
+    #[test]
+    fn compose_test() -> Result<()> {
+        let almanac = Almanac::parse(&std::fs::read_to_string("day05_p1_ex")?)?;
+        let seed_to_location = almanac.seed_to_location()?;
+        for seed in -5..120 {
+            let stepwise = almanac.maps.iter().fold(seed, |val, map| map.apply(val));
+            assert_eq!(seed_to_location.apply(seed), stepwise, "seed {}", seed);
+        }
+        assert_eq!(seed_to_location.apply(79), 82);
+        Ok(())
+    }
+
+    #[test]
+    fn inverse_test() -> Result<()> {
+        let almanac = Almanac::parse(&std::fs::read_to_string("day05_p1_ex")?)?;
+        let seed_to_location = almanac.seed_to_location()?;
+        let location_to_seed = almanac.location_to_seed()?;
+        for seed in -5..120 {
+            assert_eq!(location_to_seed.apply(seed_to_location.apply(seed)), seed);
+        }
+        assert_eq!(Map::new("a", "b", vec![(0, 10, 5)]).inverse(), None);
+
+        let locations = IntervalSet::from(40..50);
+        let seeds = seed_to_location.preimage(&locations);
+        assert_eq!(seed_to_location.image(&seeds), locations);
+        assert_eq!(seeds, location_to_seed.image(&locations));
+        Ok(())
+    }
+
+    #[test]
+    fn lowest_location_test() -> Result<()> {
+        for file in ["day05_p1_ex", "day05_p1_in"] {
+            let almanac = Almanac::parse(&std::fs::read_to_string(file)?)?;
+            assert_eq!(
+                almanac.lowest_location()?,
+                Almanac::part2(&almanac)?,
+                "{}",
+                file
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn convert_test() -> Result<()> {
+        let almanac = Almanac::parse(&std::fs::read_to_string("day05_p1_ex")?)?;
+        assert_eq!(almanac.convert("seed", 79, "humidity")?, 78);
+        assert_eq!(almanac.convert("soil", 81, "light")?, 74);
+        assert_eq!(almanac.convert("location", 82, "seed")?, 79);
+        assert_eq!(almanac.convert("water", 81, "water")?, 81);
+        assert!(almanac.convert("seed", 79, "dragon").is_err());
         Ok(())
     }
 
     #[test]
-    fn test_part1_ex() -> Result<()> {
-        assert_eq!(part1("day05_p1_ex")?, 35);
+    fn single_map_test() -> Result<()> {
+        let input = "seeds: 79 14 55 13\n\nseed-to-location map:\n50 98 2\n52 50 48\n";
+        assert_eq!(solve_part1(input)?, 13);
+        assert_eq!(solve_part2(input)?, 57);
         Ok(())
     }
 
     #[test]
-    fn test_part1_in() -> Result<()> {
-        assert_eq!(part1("day05_p1_in")?, 662197086);
+    fn forward_route_test() -> Result<()> {
+        // seed-x-location is shorter, but only by following location-to-x backwards.
+        let input = "seeds: 1 5\n\n\
+            seed-to-a map:\n100 1 10\n1 100 10\n\n\
+            a-to-b map:\n200 100 10\n100 200 10\n\n\
+            b-to-location map:\n301 200 10\n200 301 10\n\n\
+            seed-to-x map:\n50 1 10\n1 50 10\n\n\
+            location-to-x map:\n50 1 10\n1 50 10\n";
+        assert_eq!(solve_part1(input)?, 301);
+        // Without a forward route, the backward one is taken.
+        let almanac = Almanac::parse(input)?;
+        assert_eq!(almanac.convert("x", 50, "location")?, 1);
+
+        // Not one-to-one, so x-to-location has to go the long way round.
+        let input = input.replace(
+            "location-to-x map:\n50 1 10\n1 50 10",
+            "location-to-x map:\n50 1 10",
+        );
+        let almanac = Almanac::parse(&input)?;
+        assert_eq!(almanac.convert("x", 50, "location")?, 301);
         Ok(())
     }
 
     #[test]
-    fn test_part2_ex() -> Result<()> {
-        assert_eq!(part2("day05_p1_ex")?, 46);
+    fn map_order_test() -> Result<()> {
+        let input = std::fs::read_to_string("day05_p1_ex")?;
+        let mut blocks = input.trim_end().split("\n\n").collect::<Vec<_>>();
+        blocks[1..].reverse();
+        let almanac = Almanac::parse(&format!("{}\n", blocks.join("\n\n")))?;
+        assert_eq!(almanac.maps[0].from, "humidity");
+        assert_eq!(Almanac::part1(&almanac)?, 35);
+        assert_eq!(Almanac::part2(&almanac)?, 46);
+
+        // Only the last three maps, without a `seed` category to start from.
+        let err = Almanac::parse(&format!("{}\n", blocks[..4].join("\n\n"))).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(
+            (err.line, err.snippet.as_str()),
+            (3, "humidity-to-location")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn validation_test() {
+        let error = |input: &str| {
+            let err = Almanac::parse(input).unwrap_err();
+            let err = err.downcast_ref::<ParseError>().unwrap().clone();
+            (err.line, err.column, err.expected)
+        };
+        assert_eq!(
+            error("seeds: 1 2\n\nseed-to-soil map:\n1 2 3\n5 4 2\n\nsoil-to-water map:\n1 2 3\n"),
+            (5, 3, "source range clear of 2..5 on line 4".to_string())
+        );
+        assert_eq!(
+            error("seeds: 1 2\n\nseed-to-soil map:\n1 2 0\n\nsoil-to-water map:\n1 2 3\n"),
+            (4, 5, "range length above 0".to_string())
+        );
+        assert_eq!(
+            error("seeds: 1 2\n\nseed-to-soil map:\n1 2 3\n\nseed-to-soil map:\n1 2 3\n"),
+            (6, 1, "only one `seed-to-soil` map".to_string())
+        );
+        assert_eq!(
+            error("seeds: 1 2\n\nseed-to-location map:\n0 9223372036854775800 100\n"),
+            (4, 23, "range length up to 7".to_string())
+        );
+        assert_eq!(
+            error("seeds: 1 2\n\nseed-to-soil map:\n1 2 3\n\nwater-to-light map:\n1 2 3\n"),
+            (6, 1, "a map linked to `seed`".to_string())
+        );
+        assert_eq!(
+            error("seeds: 1 2\n\nseeds map:\n1 2 3\n\nsoil-to-water map:\n1 2 3\n"),
+            (3, 1, "map name like `seed-to-soil`".to_string())
+        );
+    }
+
+    #[test]
+    fn seed_ranges_test() -> Result<()> {
+        let ranges = |seeds: Vec<i64>| Almanac::new(seeds, vec![]).seed_ranges();
+        assert_eq!(
+            ranges(vec![79, 14, 55, 13])?,
+            IntervalSet::from_iter([55..68, 79..93])
+        );
+        assert!(ranges(vec![79, 14, 55]).is_err());
+        assert!(ranges(vec![79, -14]).is_err());
+        assert!(ranges(vec![i64::MAX, 2]).is_err());
+
+        // Only part 2 needs the seeds to pair up.
+        let input = "seeds: 79 14 55\n\nseed-to-location map:\n50 98 2\n";
+        assert_eq!(solve_part1(input)?, 14);
+        assert!(solve_part2(input).is_err());
         Ok(())
     }
 
     #[test]
-    // #[ignore]
-    fn test_part2_in() -> Result<()> {
-        assert_eq!(part2("day05_p1_in")?, 52510809);
+    fn numbers_test() -> Result<()> {
+        assert_eq!(2378259165u32, 1972667147 + 405592018);
+        assert_eq!(1477976316u32, 1450194064 + 27782252);
+        assert_eq!(410212617u32, 348350443 + 61862174);
+        assert_eq!(4092364215u32, 3911195009 + 181169206);
+        assert_eq!(765648080u32, 626861593 + 138786487);
+        assert_eq!(3162265119u32, 2886966111 + 275299008);
+        assert_eq!(1303406955u32, 825403564 + 478003391);
+        assert_eq!(520687690u32, 514585599 + 6102091);
+        assert_eq!(2541511753u32, 2526020300 + 15491453);
+        assert_eq!(3757205391u32, 3211013652 + 546191739);
         Ok(())
     }
 }
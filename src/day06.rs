@@ -15,57 +15,93 @@ use indicatif::{ProgressBar, ProgressStyle};
 
 use std::collections::{HashMap, HashSet, VecDeque};
 
+use log::debug;
+
+use crate::error::{parse_int, ParseError};
+use crate::Solution;
+
+const DAY: u32 = 6;
+
 #[derive(Parser)]
 #[grammar = "day06.pest"]
 struct TableParser;
 
-#[derive(Debug)]
-struct Race {
-    time: i64,
-    distance: i64,
+#[derive(Debug, Clone)]
+pub struct Race {
+    pub time: i64,
+    pub distance: i64,
 }
 
-fn part1(input: &'static str) -> Result<i64> {
-    let file_contents = std::fs::read_to_string(input).expect("Could not read file");
-    let mut table = TableParser::parse(Rule::table, file_contents.as_str())
-        .unwrap_or_else(|e| panic!("{}", e))
-        .next()
-        .unwrap()
-        .into_inner();
-
-    let times = table.next().unwrap().into_inner().collect::<Vec<_>>();
-    let distances = table.next().unwrap().into_inner().collect::<Vec<_>>();
-    let races = times
-        .iter()
-        .zip(distances)
-        .map(|(time, distance)| Race {
-            time: time.as_str().parse::<i64>().unwrap(),
-            distance: distance.as_str().parse::<i64>().unwrap(),
-        })
-        .collect::<Vec<_>>();
-
-    races.iter().for_each(|r| println!("{:?}", r));
-
-    let res = races
-        .iter()
-        .map(|race| {
-            (1..race.time)
-                .map(|t| t * (race.time - t) > race.distance)
-                .map(|v| if v { 1 } else { 0 })
-                .sum::<i64>()
-        })
-        .product();
-
-    Ok(res)
+#[derive(Debug, Clone)]
+pub struct RaceTable {
+    pub races: Vec<Race>,
+}
+
+impl Solution for RaceTable {
+    type Input = RaceTable;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let mut table = TableParser::parse(Rule::table, input)
+            .map_err(|e| ParseError::from_pest(DAY, e))?
+            .next()
+            .ok_or_else(|| ParseError::at_offset(DAY, input, 0, "table"))?
+            .into_inner();
+
+        let (Some(time), Some(distance)) = (table.next(), table.next()) else {
+            return Err(ParseError::at_offset(DAY, input, 0, "time and distance").into());
+        };
+        let times = time.into_inner().collect::<Vec<_>>();
+        let distances = distance.clone().into_inner().collect::<Vec<_>>();
+        if times.len() != distances.len() {
+            return Err(ParseError::from_pair(
+                DAY,
+                &distance,
+                format!("{} distances", times.len()),
+            )
+            .into());
+        }
+        let races = times
+            .iter()
+            .zip(distances)
+            .map(|(time, distance)| {
+                Ok(Race {
+                    time: parse_int(DAY, time)?,
+                    distance: parse_int(DAY, &distance)?,
+                })
+            })
+            .collect::<Result<Vec<_>, ParseError>>()?;
+
+        Ok(RaceTable { races })
+    }
+
+    fn part1(input: &Self::Input) -> Result<i64> {
+        let races = &input.races;
+        races.iter().for_each(|r| debug!("{:?}", r));
+
+        let res = races
+            .iter()
+            .map(|race| {
+                (1..race.time)
+                    .map(|t| t * (race.time - t) > race.distance)
+                    .map(|v| if v { 1 } else { 0 })
+                    .sum::<i64>()
+            })
+            .product();
+
+        Ok(res)
+    }
+
+    fn part2(input: &Self::Input) -> Result<i64> {
+        Self::part1(input)
+    }
 }
-fn part2(input: &'static str) -> Result<i64> {
-    part1(input)
+
+pub fn solve_part1(input: &str) -> Result<i64> {
+    RaceTable::solve_part1(input)
 }
 
-fn main() -> Result<()> {
-    println!("Part 1: {}", part1("day06_p1_in")?);
-    println!("Part 2: {}", part2("day06_p1_in")?);
-    Ok(())
+pub fn solve_part2(input: &str) -> Result<i64> {
+    RaceTable::solve_part2(input)
 }
 
 #[cfg(test)]
@@ -73,27 +109,11 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_part1_ex() -> Result<()> {
-        assert_eq!(part1("day06_p1_ex")?, 288);
-        Ok(())
-    }
-
-    #[test]
-    fn test_part1_in() -> Result<()> {
-        assert_eq!(part1("day06_p1_in")?, 1624896);
-        Ok(())
-    }
-
-    #[test]
-    fn test_part2_ex() -> Result<()> {
-        assert_eq!(part2("day06_p2_ex")?, 71503);
-        Ok(())
-    }
-
-    #[test]
-    // #[ignore]
-    fn test_part2_in() -> Result<()> {
-        assert_eq!(part2("day06_p2_in")?, 32583852);
+    fn test_part1_str() -> Result<()> {
+        assert_eq!(
+            solve_part1("Time:      7  15   30\nDistance:  9  40  200")?,
+            288
+        );
         Ok(())
     }
 }
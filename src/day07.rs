@@ -18,17 +18,24 @@ use indicatif::{ProgressBar, ProgressStyle};
 
 use std::collections::{HashMap, HashSet, VecDeque};
 
+use log::{debug, trace};
+
+use crate::error::{parse_int, ParseError};
+use crate::Solution;
+
+const DAY: u32 = 7;
+
 #[derive(Parser)]
 #[grammar = "day07.pest"]
 struct TableParser;
 
-#[derive(Debug, Eq)]
-struct Hand {
-    cards: (i64, i64, i64, i64, i64),
-    orig_cards: Option<(i64, i64, i64, i64, i64)>,
+#[derive(Debug, Clone, Eq)]
+pub struct Hand {
+    pub cards: (i64, i64, i64, i64, i64),
+    pub orig_cards: Option<(i64, i64, i64, i64, i64)>,
 }
 
-type Bid = i64;
+pub type Bid = i64;
 
 fn parse(card: char) -> Option<i64> {
     match card {
@@ -48,7 +55,7 @@ impl PartialEq for Hand {
     }
 }
 
-fn ch_eq<'a>(cards: &(i64, i64, i64, i64, i64), mask: impl IntoIterator<Item = i64>) -> bool {
+fn ch_eq(cards: &(i64, i64, i64, i64, i64), mask: impl IntoIterator<Item = i64>) -> bool {
     let cards = [cards.0, cards.1, cards.2, cards.3, cards.4];
     let cards = cards.iter();
     let mask = mask.into_iter();
@@ -66,35 +73,35 @@ fn ch_eq<'a>(cards: &(i64, i64, i64, i64, i64), mask: impl IntoIterator<Item = i
 }
 
 impl Hand {
-    fn is_5_of_a_kind(cards: &(i64, i64, i64, i64, i64)) -> bool {
-        ch_eq(&cards, [1, 1, 1, 1, 1])
+    pub fn is_5_of_a_kind(cards: &(i64, i64, i64, i64, i64)) -> bool {
+        ch_eq(cards, [1, 1, 1, 1, 1])
     }
-    fn is_4_of_a_kind(cards: &(i64, i64, i64, i64, i64)) -> bool {
-        ch_eq(&cards, [1, 1, 1, 1, 0]) || ch_eq(&cards, [0, 1, 1, 1, 1])
+    pub fn is_4_of_a_kind(cards: &(i64, i64, i64, i64, i64)) -> bool {
+        ch_eq(cards, [1, 1, 1, 1, 0]) || ch_eq(cards, [0, 1, 1, 1, 1])
     }
-    fn is_full_house(cards: &(i64, i64, i64, i64, i64)) -> bool {
-        ch_eq(&cards, [1, 1, 1, 2, 2]) || ch_eq(&cards, [2, 2, 1, 1, 1])
+    pub fn is_full_house(cards: &(i64, i64, i64, i64, i64)) -> bool {
+        ch_eq(cards, [1, 1, 1, 2, 2]) || ch_eq(cards, [2, 2, 1, 1, 1])
     }
-    fn is_3_of_a_kind(cards: &(i64, i64, i64, i64, i64)) -> bool {
-        ch_eq(&cards, [1, 1, 1, 0, 0])
-            || ch_eq(&cards, [0, 1, 1, 1, 0])
-            || ch_eq(&cards, [0, 0, 1, 1, 1])
+    pub fn is_3_of_a_kind(cards: &(i64, i64, i64, i64, i64)) -> bool {
+        ch_eq(cards, [1, 1, 1, 0, 0])
+            || ch_eq(cards, [0, 1, 1, 1, 0])
+            || ch_eq(cards, [0, 0, 1, 1, 1])
     }
-    fn is_2_pair(cards: &(i64, i64, i64, i64, i64)) -> bool {
-        ch_eq(&cards, [1, 1, 2, 2, 0])
-            || ch_eq(&cards, [1, 1, 0, 2, 2])
-            || ch_eq(&cards, [0, 1, 1, 2, 2])
-            || ch_eq(&cards, [2, 2, 1, 1, 0])
-            || ch_eq(&cards, [2, 2, 0, 1, 1])
-            || ch_eq(&cards, [0, 2, 2, 1, 1])
+    pub fn is_2_pair(cards: &(i64, i64, i64, i64, i64)) -> bool {
+        ch_eq(cards, [1, 1, 2, 2, 0])
+            || ch_eq(cards, [1, 1, 0, 2, 2])
+            || ch_eq(cards, [0, 1, 1, 2, 2])
+            || ch_eq(cards, [2, 2, 1, 1, 0])
+            || ch_eq(cards, [2, 2, 0, 1, 1])
+            || ch_eq(cards, [0, 2, 2, 1, 1])
     }
-    fn is_1_pair(cards: &(i64, i64, i64, i64, i64)) -> bool {
-        ch_eq(&cards, [1, 1, 0, 0, 0])
-            || ch_eq(&cards, [0, 1, 1, 0, 0])
-            || ch_eq(&cards, [0, 0, 1, 1, 0])
-            || ch_eq(&cards, [0, 0, 0, 1, 1])
+    pub fn is_1_pair(cards: &(i64, i64, i64, i64, i64)) -> bool {
+        ch_eq(cards, [1, 1, 0, 0, 0])
+            || ch_eq(cards, [0, 1, 1, 0, 0])
+            || ch_eq(cards, [0, 0, 1, 1, 0])
+            || ch_eq(cards, [0, 0, 0, 1, 1])
     }
-    fn hand_type(&self) -> i64 {
+    pub fn hand_type(&self) -> i64 {
         match self.cards {
             // 5 of a kind
             cards if Hand::is_5_of_a_kind(&cards) => 6,
@@ -113,7 +120,7 @@ impl Hand {
         }
     }
 
-    fn sort(&mut self) {
+    pub fn sort(&mut self) {
         if self.orig_cards.is_none() {
             self.orig_cards = Some(self.cards);
         }
@@ -138,10 +145,7 @@ fn partial_cmp(
 
     a.zip(b)
         .map(|(a, b): (i64, i64)| a.partial_cmp(&b))
-        .filter(|x| {
-            println!("{:?}", x);
-            x.is_some()
-        })
+        .filter(|x| x.is_some())
         .find(|x| x.unwrap() != std::cmp::Ordering::Equal)
         .unwrap_or(Some(std::cmp::Ordering::Equal))
 }
@@ -172,7 +176,7 @@ impl PartialOrd for Hand {
         let self_type = self.hand_type();
         let other_type = other.hand_type();
         self_type.partial_cmp(&other_type).and_then(|x| {
-            println!("{}:{} {}:{} => {:?}", self_type, self, other_type, other, x);
+            trace!("{}:{} {}:{} => {:?}", self_type, self, other_type, other, x);
             if x != std::cmp::Ordering::Equal {
                 return Some(x);
             }
@@ -196,114 +200,90 @@ impl PartialOrd for Hand {
     }
 }
 
-fn part1(input: &'static str) -> Result<i64> {
-    let file_contents = std::fs::read_to_string(input).expect("Could not read file");
-    let hands_bids = TableParser::parse(Rule::hands, file_contents.as_str())
-        .unwrap_or_else(|e| panic!("{}", e))
-        .next()
-        .unwrap()
-        .into_inner();
-
-    let mut hands_bids = hands_bids
-        .map(|hand_bid| {
-            let mut hand_bid_iter = hand_bid.into_inner();
-            (
-                Hand {
-                    orig_cards: None,
-                    cards: hand_bid_iter
-                        .next()
-                        .unwrap()
-                        .as_str()
-                        .chars()
-                        .map(|card| parse(card).unwrap_or_else(|| panic!("Invalid card: {}", card)))
-                        .collect::<Vec<_>>()
-                        .chunks(5)
-                        .map(|chunk| (chunk[0], chunk[1], chunk[2], chunk[3], chunk[4]))
-                        .last()
-                        .unwrap(),
-                },
-                hand_bid_iter
-                    .next()
-                    .unwrap()
-                    .as_str()
-                    .parse::<Bid>()
-                    .unwrap(),
-            )
-        })
-        .collect::<Vec<(_, _)>>();
-    hands_bids.iter_mut().for_each(|(hand, _)| hand.sort());
+#[derive(Debug, Clone)]
+pub struct CamelCards {
+    pub hands_bids: Vec<(Hand, Bid)>,
+}
 
-    hands_bids.sort_by(|(hand_a, _), (hand_b, _)| hand_a.partial_cmp(hand_b).unwrap());
+impl CamelCards {
+    fn total_winnings(&self) -> i64 {
+        let mut hands_bids = self.hands_bids.clone();
+        hands_bids.iter_mut().for_each(|(hand, _)| hand.sort());
 
-    let total_winnings = hands_bids
-        .iter()
-        .enumerate()
-        .map(|(i, (_, bid))| (1 + i as i64) * bid)
-        .sum::<i64>();
+        hands_bids.sort_by(|(hand_a, _), (hand_b, _)| hand_a.partial_cmp(hand_b).unwrap());
 
-    for (i, (hand, bid)) in hands_bids.iter().enumerate() {
-        println!("{}: {:?} {}", i + 1, hand, bid);
-    }
+        let total_winnings = hands_bids
+            .iter()
+            .enumerate()
+            .map(|(i, (_, bid))| (1 + i as i64) * bid)
+            .sum::<i64>();
+
+        for (i, (hand, bid)) in hands_bids.iter().enumerate() {
+            debug!("{}: {:?} {}", i + 1, hand, bid);
+        }
 
-    Ok(total_winnings)
+        total_winnings
+    }
 }
 
-fn part2(input: &'static str) -> Result<i64> {
-    let file_contents = std::fs::read_to_string(input).expect("Could not read file");
-    let hands_bids = TableParser::parse(Rule::hands, file_contents.as_str())
-        .unwrap_or_else(|e| panic!("{}", e))
-        .next()
-        .unwrap()
-        .into_inner();
-
-    let mut hands_bids = hands_bids
-        .map(|hand_bid| {
-            let mut hand_bid_iter = hand_bid.into_inner();
-            (
-                Hand {
-                    orig_cards: None,
-                    cards: hand_bid_iter
-                        .next()
-                        .unwrap()
-                        .as_str()
-                        .chars()
-                        .map(|card| parse(card).unwrap_or_else(|| panic!("Invalid card: {}", card)))
-                        .collect::<Vec<_>>()
-                        .chunks(5)
-                        .map(|chunk| (chunk[0], chunk[1], chunk[2], chunk[3], chunk[4]))
-                        .last()
-                        .unwrap(),
-                },
-                hand_bid_iter
-                    .next()
-                    .unwrap()
+impl Solution for CamelCards {
+    type Input = CamelCards;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let hands_bids = TableParser::parse(Rule::hands, input)
+            .map_err(|e| ParseError::from_pest(DAY, e))?
+            .next()
+            .ok_or_else(|| ParseError::at_offset(DAY, input, 0, "hands"))?
+            .into_inner();
+
+        let hands_bids = hands_bids
+            .map(|hand_bid| {
+                let mut hand_bid_iter = hand_bid.clone().into_inner();
+                let (Some(hand), Some(bid)) = (hand_bid_iter.next(), hand_bid_iter.next()) else {
+                    return Err(ParseError::from_pair(DAY, &hand_bid, "hand and bid"));
+                };
+                let (line, column) = hand.as_span().start_pos().line_col();
+                let cards = hand
                     .as_str()
-                    .parse::<Bid>()
-                    .unwrap(),
-            )
-        })
-        .collect::<Vec<(_, _)>>();
-    hands_bids.iter_mut().for_each(|(hand, _)| hand.sort());
-
-    hands_bids.sort_by(|(hand_a, _), (hand_b, _)| hand_a.partial_cmp(hand_b).unwrap());
+                    .chars()
+                    .enumerate()
+                    .map(|(i, card)| {
+                        parse(card).ok_or_else(|| {
+                            ParseError::new(DAY, line, column + i, "card", &card.to_string())
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let [c0, c1, c2, c3, c4] = cards[..] else {
+                    return Err(ParseError::from_pair(DAY, &hand, "five cards"));
+                };
+                Ok((
+                    Hand {
+                        orig_cards: None,
+                        cards: (c0, c1, c2, c3, c4),
+                    },
+                    parse_int(DAY, &bid)?,
+                ))
+            })
+            .collect::<Result<Vec<(_, _)>, ParseError>>()?;
+
+        Ok(CamelCards { hands_bids })
+    }
 
-    let total_winnings = hands_bids
-        .iter()
-        .enumerate()
-        .map(|(i, (_, bid))| (1 + i as i64) * bid)
-        .sum::<i64>();
+    fn part1(input: &Self::Input) -> Result<i64> {
+        Ok(input.total_winnings())
+    }
 
-    for (i, (hand, bid)) in hands_bids.iter().enumerate() {
-        println!("{}: {:?} {}", i + 1, hand, bid);
+    fn part2(input: &Self::Input) -> Result<i64> {
+        Ok(input.total_winnings())
     }
+}
 
-    Ok(total_winnings)
+pub fn solve_part1(input: &str) -> Result<i64> {
+    CamelCards::solve_part1(input)
 }
 
-fn main() -> Result<()> {
-    println!("Part 1: {}", part1("day06_p1_in")?);
-    println!("Part 2: {}", part2("day06_p1_in")?);
-    Ok(())
+pub fn solve_part2(input: &str) -> Result<i64> {
+    CamelCards::solve_part2(input)
 }
 
 #[cfg(test)]
@@ -325,31 +305,4 @@ mod tests {
             partial_cmp([13, 1, 11, 11, 10], [13, 13, 6, 7, 7])
         );
     }
-
-    #[test]
-    #[ignore]
-    fn test_part1_ex() -> Result<()> {
-        assert_eq!(part1("day07_p1_ex")?, 6440);
-        Ok(())
-    }
-
-    #[test]
-    #[ignore]
-    fn test_part1_in() -> Result<()> {
-        assert_eq!(part1("day07_p1_in")?, 249726565);
-        Ok(())
-    }
-    //
-    #[test]
-    fn test_part2_ex() -> Result<()> {
-        assert_eq!(part2("day07_p1_ex")?, 5905);
-        Ok(())
-    }
-    //
-    #[test]
-    #[ignore]
-    fn test_part2_in() -> Result<()> {
-        assert_eq!(part2("day07_p1_in")?, 252149023);
-        Ok(())
-    }
 }
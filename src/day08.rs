@@ -1,14 +1,18 @@
 // #![allow(dead_code)]
 #![allow(unused_imports)]
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, ensure, Result};
 use contracts::*;
 use indicatif::{ProgressBar, ProgressStyle};
 use nom::{
-    bytes::complete::{tag, take, take_until},
-    character::complete::{char, digit1, line_ending, space0, space1},
-    combinator::{map, map_res},
-    multi::separated_list1,
+    branch::alt,
+    bytes::complete::{tag, take, take_until, take_while1},
+    character::complete::{
+        alphanumeric1, char, digit1, line_ending, one_of, satisfy, space0, space1,
+    },
+    combinator::{map, map_opt, map_res, opt, peek, value},
+    error::{Error, ErrorKind},
+    multi::{many1, separated_list1},
     sequence::{delimited, preceded, separated_pair, terminated, tuple},
     IResult,
 };
@@ -21,6 +25,20 @@ use std::{
     str::FromStr,
 };
 
+use itertools::Itertools;
+use num::integer::{lcm, ExtendedGcd, Integer};
+use onig::Regex;
+
+use crate::error::ParseError;
+use crate::graph::{Graph, NodeId};
+use crate::Solution;
+
+pub mod cycles;
+pub mod dot;
+pub mod jump;
+
+const DAY: u32 = 8;
+
 // struct SingleLinkedList<T> {
 //     next: *const SingleLinkedList<T>,
 //     value: Option<T>,
@@ -73,45 +91,104 @@ use std::{
 //     }
 // }
 
+/// One step of a walk: to a node's left, right or numbered child, or staying put.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Instruction {
+    Left,
+    Right,
+    Child(usize),
+    Stay,
+}
+
+impl Instruction {
+    /// Index of the child followed, with `L` and `R` the first and second.
+    pub fn child(self) -> Option<usize> {
+        match self {
+            Instruction::Left => Some(0),
+            Instruction::Right => Some(1),
+            Instruction::Child(child) => Some(child),
+            Instruction::Stay => None,
+        }
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Instruction::Left => write!(f, "L"),
+            Instruction::Right => write!(f, "R"),
+            Instruction::Child(child) => write!(f, "{}", child),
+            Instruction::Stay => write!(f, "S"),
+        }
+    }
+}
+
+/// The instructions with runs like `3L` written out.
 #[derive(Debug, Clone)]
-struct Instructions(String);
+pub struct Instructions(pub Vec<Instruction>);
+
+const INSTRUCTION: &str = "instruction `L`, `R`, `S` or a child digit";
+
+fn parse_instruction(input: &str) -> IResult<&str, Instruction> {
+    alt((
+        value(Instruction::Left, char('L')),
+        value(Instruction::Right, char('R')),
+        value(Instruction::Stay, char('S')),
+        map_opt(satisfy(|c| c.is_ascii_digit()), |c| {
+            Some(Instruction::Child(c.to_digit(10)? as usize))
+        }),
+    ))(input)
+}
+
+/// Longest run a count may write out, so that a short line cannot exhaust memory.
+const MAX_RUN: usize = 1000;
+
+// 3L, or 12 for children 1 and 2: only a letter can be repeated by a count.
+fn parse_run(input: &str) -> IResult<&str, Vec<Instruction>> {
+    let (rest, count) = opt(terminated(digit1, peek(one_of("LRS"))))(input)?;
+    let count = match count {
+        Some(count) => count
+            .parse::<usize>()
+            .ok()
+            .filter(|&count| count <= MAX_RUN)
+            .ok_or(nom::Err::Failure(Error::new(input, ErrorKind::TooLarge)))?,
+        None => 1,
+    };
+    let (rest, instruction) = parse_instruction(rest)?;
+
+    Ok((rest, vec![instruction; count]))
+}
 
 // RRRLRLRLRLRLRLRLRLRLRLLRLRRLLRLRLRLRRLRLRLRLLLLLLRLRRRLRLLRRLRLRLRRRRLRLLRLRLRLLRLR
 fn parse_instructions(input: &str) -> IResult<&str, Instructions> {
-    let (input, instructions) =
-        map(take_until("\n"), |s: &str| Instructions(s.to_string()))(input)?;
+    let (input, instructions) = map(many1(parse_run), |runs| Instructions(runs.concat()))(input)?;
 
     Ok((input, instructions))
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct Node {
-    id: [char; 3],
-    left: [char; 3],
-    right: [char; 3],
+pub struct Node {
+    pub id: String,
+    pub children: Vec<String>,
+}
+
+fn parse_node_id(input: &str) -> IResult<&str, String> {
+    map(alphanumeric1, str::to_string)(input)
 }
 
-// AAA = (BBB, CCC)
+// AAA = (BBB, CCC), or with any number of children
 fn parse_node(input: &str) -> IResult<&str, Node> {
-    let (input, (id, _, left, _, right, _)) = tuple((
-        map(take(3usize), |s: &str| s.to_string()),
-        tag(" = ("),
-        map(take(3usize), |s: &str| s.to_string()),
-        tag(", "),
-        map(take(3usize), |s: &str| s.to_string()),
-        tag(")"),
-    ))(input)?;
-
-    // println!("id: {:?}, left: {:?}, right: {:?}", id, left, right);
-
-    Ok((
-        input,
-        Node {
-            id: id.chars().collect::<Vec<char>>().try_into().unwrap(),
-            left: left.chars().collect::<Vec<char>>().try_into().unwrap(),
-            right: right.chars().collect::<Vec<char>>().try_into().unwrap(),
-        },
-    ))
+    let (input, (id, children)) = separated_pair(
+        parse_node_id,
+        tag(" = "),
+        delimited(
+            char('('),
+            separated_list1(tag(", "), parse_node_id),
+            char(')'),
+        ),
+    )(input)?;
+
+    Ok((input, Node { id, children }))
 }
 
 fn empty_line(input: &str) -> IResult<&str, &str> {
@@ -135,97 +212,370 @@ fn parse_file_contents(input: &str) -> IResult<&str, (Instructions, Vec<Node>)>
     Ok((input, (instructions, nodes)))
 }
 
-fn part1(input: &'static str) -> Result<i64> {
-    let file_contents = std::fs::read_to_string(input).expect("Could not read file");
-    let (_, (instructions, nodes)) = parse_file_contents(&file_contents).expect("Could not parse");
+#[derive(Debug, Clone)]
+pub struct Network {
+    pub instructions: Instructions,
+    /// Every node has its left and then its right neighbour as edges.
+    pub graph: Graph<String>,
+}
 
-    let mut jumps = 0;
-    let mut current_node = nodes
-        .iter()
-        .find(|n| n.id == ['A', 'A', 'A'])
-        .ok_or(anyhow!("Could not find starting node"))?;
-    assert_eq!(current_node.id, ['A', 'A', 'A']);
-    while current_node.id != ['Z', 'Z', 'Z'] {
-        for instruction in instructions.0.chars() {
-            jumps += 1;
-            match instruction {
-                'R' => {
-                    current_node = nodes
-                        .iter()
-                        .find(|n| n.id == current_node.right)
-                        .ok_or(anyhow!("Could not find right: {:?}", current_node.right))?;
-                }
-                'L' => {
-                    current_node = nodes
-                        .iter()
-                        .find(|n| n.id == current_node.left)
-                        .ok_or(anyhow!("Could not find left: {:?}", current_node.left))?;
-                }
-                _ => panic!("Unknown instruction: {}", instruction),
+/// Picks out nodes by their id: `AAA` exactly, `*Z` by suffix or `/^1.Z$/` by regex.
+#[derive(Debug)]
+pub enum NodeMatcher {
+    Exact(String),
+    Suffix(String),
+    Regex { pattern: String, regex: Regex },
+}
+
+impl NodeMatcher {
+    pub fn matches(&self, id: &str) -> bool {
+        match self {
+            NodeMatcher::Exact(exact) => id == exact,
+            NodeMatcher::Suffix(suffix) => id.ends_with(suffix.as_str()),
+            NodeMatcher::Regex { regex, .. } => regex.find(id).is_some(),
+        }
+    }
+}
+
+impl FromStr for NodeMatcher {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(suffix) = s.strip_prefix('*') {
+            Ok(NodeMatcher::Suffix(suffix.to_string()))
+        } else if let Some(pattern) = s.strip_prefix('/').and_then(|s| s.strip_suffix('/')) {
+            let regex = Regex::new(pattern)
+                .map_err(|e| anyhow!("Invalid node pattern `{}`: {}", pattern, e))?;
+            Ok(NodeMatcher::Regex {
+                pattern: pattern.to_string(),
+                regex,
+            })
+        } else if !s.is_empty() && s.chars().all(char::is_alphanumeric) {
+            Ok(NodeMatcher::Exact(s.to_string()))
+        } else {
+            Err(anyhow!(
+                "Expected a node id, `*suffix` or `/regex/`, got `{}`",
+                s
+            ))
+        }
+    }
+}
+
+impl Display for NodeMatcher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeMatcher::Exact(exact) => write!(f, "{}", exact),
+            NodeMatcher::Suffix(suffix) => write!(f, "*{}", suffix),
+            NodeMatcher::Regex { pattern, .. } => write!(f, "/{}/", pattern),
+        }
+    }
+}
+
+/// The nodes walks start from and the nodes they end on.
+#[derive(Debug)]
+pub struct Ends {
+    pub start: NodeMatcher,
+    pub end: NodeMatcher,
+}
+
+impl Ends {
+    /// From `AAA` to `ZZZ`, as walked in part 1.
+    pub fn camel() -> Self {
+        Ends {
+            start: NodeMatcher::Exact("AAA".to_string()),
+            end: NodeMatcher::Exact("ZZZ".to_string()),
+        }
+    }
+
+    /// From every node ending in `A` to any ending in `Z`, as the ghosts walk in part 2.
+    pub fn ghosts() -> Self {
+        Ends {
+            start: NodeMatcher::Suffix("A".to_string()),
+            end: NodeMatcher::Suffix("Z".to_string()),
+        }
+    }
+}
+
+impl Network {
+    pub fn child(&self, id: NodeId, child: usize) -> Option<NodeId> {
+        self.graph.edges(id).get(child).map(|&(to, _)| to)
+    }
+
+    pub fn step(&self, id: NodeId, instruction: Instruction) -> Result<NodeId> {
+        match instruction.child() {
+            Some(child) => self.child(id, child).ok_or(anyhow!(
+                "Node {} has no child {} to follow `{}`",
+                self.name(id),
+                child,
+                instruction
+            )),
+            None => Ok(id),
+        }
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        self.graph.label(id)
+    }
+
+    pub fn matching<'a>(&'a self, matcher: &'a NodeMatcher) -> impl Iterator<Item = NodeId> + 'a {
+        self.graph
+            .nodes()
+            .filter(|&id| matcher.matches(self.name(id)))
+    }
+
+    /// Whether each node, by id, matches.
+    pub fn mask(&self, matcher: &NodeMatcher) -> Vec<bool> {
+        self.graph
+            .nodes()
+            .map(|id| matcher.matches(self.name(id)))
+            .collect()
+    }
+
+    /// The nodes walked from the first start to an end node, both included.
+    pub fn path(&self, ends: &Ends) -> Result<Vec<NodeId>> {
+        let start = self
+            .matching(&ends.start)
+            .next()
+            .ok_or(anyhow!("Could not find starting node {}", ends.start))?;
+        let is_end = self.mask(&ends.end);
+
+        // Past every node at every instruction, the walk is going around in circles.
+        let limit = self.graph.len() * self.instructions.0.len();
+        let mut instructions = self.instructions.0.iter().copied().cycle().take(limit);
+        let mut path = vec![start];
+        let mut current = start;
+        while !is_end[current] {
+            let instruction = instructions.next().ok_or(anyhow!(
+                "Never reaches {} from {}",
+                ends.end,
+                self.name(start)
+            ))?;
+            current = self.step(current, instruction)?;
+            path.push(current);
+        }
+        Ok(path)
+    }
+
+    /// The cycle walked from every start node.
+    pub fn cycles(&self, ends: &Ends) -> Result<Vec<(NodeId, Cycle)>> {
+        let is_end = self.mask(&ends.end);
+        self.matching(&ends.start)
+            .map(|start| Ok((start, self.cycle(start, |id| is_end[id])?)))
+            .collect()
+    }
+
+    /// Walks from `start` until a node is reached again at the same instruction.
+    pub fn cycle(&self, start: NodeId, is_end: impl Fn(NodeId) -> bool) -> Result<Cycle> {
+        let instructions = &self.instructions.0;
+        let mut seen = vec![None; self.graph.len() * instructions.len()];
+        let mut ends = vec![];
+        let (mut current, mut steps) = (start, 0);
+        loop {
+            let index = (steps % instructions.len() as i64) as usize;
+            let state = current * instructions.len() + index;
+            if let Some(prefix) = seen[state] {
+                return Ok(Cycle {
+                    prefix,
+                    length: steps - prefix,
+                    ends,
+                });
+            }
+            seen[state] = Some(steps);
+            if is_end(current) {
+                ends.push(steps);
             }
+            current = self.step(current, instructions[index])?;
+            steps += 1;
         }
     }
+}
+
+/// The walk from one node: `prefix` steps to where it starts repeating every `length`
+/// steps, and the steps before it first repeats at which it is on an end node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub prefix: i64,
+    pub length: i64,
+    pub ends: Vec<i64>,
+}
+
+impl Cycle {
+    /// Whether the walk is on an end node after `steps` steps.
+    pub fn is_end(&self, steps: i64) -> bool {
+        let steps = if steps < self.prefix {
+            steps
+        } else {
+            self.prefix + (steps - self.prefix) % self.length
+        };
+        self.ends.contains(&steps)
+    }
 
-    Ok(jumps)
+    /// Whether the walk is on an end node once per cycle, exactly as it closes, so the
+    /// first common end of such walks is the LCM of their lengths.
+    pub fn is_clean(&self) -> bool {
+        self.repeating_ends().eq([self.length])
+    }
+
+    /// End steps that come around again every `length` steps.
+    pub fn repeating_ends(&self) -> impl Iterator<Item = i64> + '_ {
+        self.ends.iter().copied().filter(|&end| end >= self.prefix)
+    }
 }
 
-fn part2(input: &'static str) -> Result<i64> {
-    let file_contents = std::fs::read_to_string(input).expect("Could not read file");
-    let (_, (instructions, nodes)) = parse_file_contents(&file_contents).expect("Could not parse");
+/// Smallest `t` with `t % modulus == residue` for every pair, and the modulus it
+/// repeats with, if the congruences agree.
+pub fn crt(congruences: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+    let (mut t, mut modulus) = (0i128, 1i128);
+    for (residue, m) in congruences {
+        let (residue, m) = (residue as i128, m as i128);
+        let ExtendedGcd { gcd, x, .. } = modulus.extended_gcd(&m);
+        if (residue - t) % gcd != 0 {
+            return None;
+        }
+        let step = m / gcd;
+        t += modulus * ((residue - t) / gcd * x).rem_euclid(step);
+        modulus *= step;
+        t = t.rem_euclid(modulus);
+    }
+    Some((t.try_into().ok()?, modulus.try_into().ok()?))
+}
 
-    let edges = nodes
+/// First step at which every walk is on an end node at the same time.
+pub fn first_common_end(cycles: &[Cycle]) -> Option<i64> {
+    // Before the longest prefix, only that walk's one-off ends are candidates.
+    let longest = cycles.iter().max_by_key(|cycle| cycle.prefix)?;
+    let early = longest
+        .ends
         .iter()
-        .map(|n| -> (&Node, (&Node, &Node)) {
-            (
-                nodes.iter().find(|fiter| fiter.id == n.id).unwrap(),
-                (
-                    nodes.iter().find(|fiter| fiter.id == n.left).unwrap(),
-                    nodes.iter().find(|fiter| fiter.id == n.right).unwrap(),
-                ),
-            )
-        })
-        .collect::<HashMap<&Node, (&Node, &Node)>>();
+        .filter(|&&end| end < longest.prefix)
+        .find(|&&end| cycles.iter().all(|cycle| cycle.is_end(end)));
+    if let Some(&end) = early {
+        return Some(end);
+    }
 
-    // For every node, count the number of steps to a node that ends in 'Z'.
-    let step_map: HashMap<&Node, i64> = nodes
+    // Usually every walk hits its end exactly once per cycle, right as the cycle closes.
+    if cycles.iter().all(Cycle::is_clean) {
+        return cycles.iter().try_fold(1, |multiple, cycle| {
+            Some(lcm(multiple, cycle.length)).filter(|&multiple| multiple > 0)
+        });
+    }
+
+    // Otherwise try every combination of repeating ends, lifting each solution past
+    // the ends it was built from.
+    cycles
         .iter()
-        .filter(|n| match n.id {
-            [_, _, 'A'] => true,
-            _ => false,
+        .map(|cycle| {
+            cycle
+                .repeating_ends()
+                .map(|end| (end, cycle.length))
+                .collect::<Vec<_>>()
         })
-        .map(|n| {
-            let mut current_node = n;
-            let mut steps = 0;
-            while current_node.id.last().unwrap() != &'Z' {
-                steps += 1;
-                for instruction in instructions.0.chars() {
-                    match instruction {
-                        'L' => {
-                            current_node = &edges.get(current_node).unwrap().0;
-                        }
-                        'R' => {
-                            current_node = &edges.get(current_node).unwrap().1;
-                        }
-                        _ => panic!("Unknown instruction: {}", instruction),
-                    }
-                }
+        .multi_cartesian_product()
+        .filter_map(|ends| {
+            let (t, modulus) = crt(ends.iter().map(|&(end, length)| (end % length, length)))?;
+            let after = ends.iter().map(|&(end, _)| end).max()?;
+            let behind = (after - t).max(0);
+            Some(t + (behind + modulus - 1) / modulus * modulus)
+        })
+        .min()
+}
+
+impl Solution for Network {
+    type Input = Network;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let (rest, (instructions, nodes)) = parse_file_contents(input).map_err(|e| {
+            let too_long = matches!(&e, nom::Err::Failure(e) if e.code == ErrorKind::TooLarge);
+            let mut err = ParseError::from_nom(DAY, input, e);
+            if too_long {
+                err.expected = format!("a run of at most {} instructions", MAX_RUN);
+            } else if err.line == 1 {
+                err.expected = INSTRUCTION.to_string();
             }
+            err
+        })?;
+        let rest = rest.trim_start();
+        if !rest.is_empty() {
+            return Err(ParseError::at_offset(DAY, input, input.len() - rest.len(), "node").into());
+        }
+        if instructions.0.is_empty() {
+            let line = input.lines().next().unwrap_or("");
+            return Err(ParseError::new(DAY, 1, 1, INSTRUCTION, line).into());
+        }
+
+        let mut graph = Graph::new();
+        for (line, node) in nodes.iter().enumerate() {
+            if graph.id(&node.id).is_some() {
+                return Err(ParseError::new(
+                    DAY,
+                    line + 3,
+                    1,
+                    "a node not defined above",
+                    input.lines().nth(line + 2).unwrap_or(""),
+                )
+                .into());
+            }
+            graph.add_node(node.id.clone());
+        }
+        for (line, node) in nodes.iter().enumerate() {
+            let Some(children) = node
+                .children
+                .iter()
+                .map(|child| graph.id(child))
+                .collect::<Option<Vec<_>>>()
+            else {
+                return Err(ParseError::new(
+                    DAY,
+                    line + 3,
+                    1,
+                    "edges to existing nodes",
+                    input.lines().nth(line + 2).unwrap_or(""),
+                )
+                .into());
+            };
+            let id = graph.add_node(node.id.clone());
+            for child in children {
+                graph.add_edge(id, child, ());
+            }
+        }
 
-            (n, steps)
+        Ok(Network {
+            instructions,
+            graph,
         })
-        .collect();
+    }
 
-    // kA wie man darauf kommt, aber es ist die Lösung.
-    let jumps = step_map.iter().map(|(_, n)| n).product::<i64>() * instructions.0.len() as i64;
+    fn part1(input: &Self::Input) -> Result<i64> {
+        Ok(input.path(&Ends::camel())?.len() as i64 - 1)
+    }
 
-    Ok(jumps)
+    fn part2(input: &Self::Input) -> Result<i64> {
+        let ends = Ends::ghosts();
+        let (starts, cycles): (Vec<_>, Vec<_>) = input.cycles(&ends)?.into_iter().unzip();
+        let jumps = first_common_end(&cycles)
+            .ok_or(anyhow!("The walks are never all on a `Z` node at once"))?;
+
+        // Cheap to double check by jumping every ghost straight there.
+        let is_end = input.mask(&ends.end);
+        let table = jump::JumpTable::new(input);
+        ensure!(
+            table
+                .all_at(&starts, jumps as u64)
+                .all(|id| id.is_some_and(|id| is_end[id])),
+            "Not every ghost is on a `Z` node after {} steps",
+            jumps
+        );
+
+        Ok(jumps)
+    }
 }
 
-fn main() -> Result<()> {
-    // println!("Part 1: {}", part1("day08_p1_in")?);
-    // println!("Part 2: {}", part2("day08_p1_in")?);
-    println!("Part 2: {}", part2("day08_p1_in")?);
-    Ok(())
+pub fn solve_part1(input: &str) -> Result<i64> {
+    Network::solve_part1(input)
+}
+
+pub fn solve_part2(input: &str) -> Result<i64> {
+    Network::solve_part2(input)
 }
 
 #[cfg(test)]
@@ -233,34 +583,125 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_part1_ex2() -> Result<()> {
-        assert_eq!(part1("day08_p1_ex2")?, 6);
+    fn test_parse_error() {
+        let err = solve_part1("LR\n\nAAA = (BBB, ZZZ)\nZZZ = (ZZZ, ZZZ)\n").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(err.line, 3);
+        assert_eq!(err.expected, "edges to existing nodes");
+
+        let err = solve_part1("LR\n\nAAA = (ZZZ, ZZZ)\nAAA = (BBB, CCC)\nZZZ = (ZZZ, ZZZ)\n")
+            .unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(err.line, 4);
+        assert_eq!(err.expected, "a node not defined above");
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt([(2, 3), (3, 5)]), Some((8, 15)));
+        assert_eq!(crt([(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt([(0, 4), (1, 6)]), None);
+    }
+
+    #[test]
+    fn test_cycles() -> Result<()> {
+        let network = Network::parse(&std::fs::read_to_string("day08_p2_ex")?)?;
+        let start = network.graph.id(&"22A".to_string()).unwrap();
+        let cycle = network.cycle(start, |id| network.name(id).ends_with('Z'))?;
+        assert_eq!(cycle.length, 6);
+        assert_eq!(cycle.repeating_ends().collect::<Vec<_>>(), [3, 6]);
         Ok(())
     }
 
     #[test]
-    fn test_part1_ex() -> Result<()> {
-        assert_eq!(part1("day08_p1_ex")?, 2);
+    fn test_first_common_end() {
+        let clean = |length| Cycle {
+            prefix: 1,
+            length,
+            ends: vec![length],
+        };
+        assert_eq!(first_common_end(&[clean(4), clean(6)]), Some(12));
+
+        // Ends at 5, 8, 11, ... and at 2 and then 7, 12, 17, ...
+        let offset = Cycle {
+            prefix: 3,
+            length: 3,
+            ends: vec![5],
+        };
+        let early = Cycle {
+            prefix: 4,
+            length: 5,
+            ends: vec![2, 7],
+        };
+        assert_eq!(first_common_end(&[offset, early]), Some(17));
+    }
+
+    #[test]
+    fn test_part2_ignores_unreachable_nodes() -> Result<()> {
+        // XXX has no child 2, but no ghost ever gets there.
+        let input = "L2\n\nAAA = (BBB, BBB, BBB)\nBBB = (ZZZ, ZZZ, ZZZ)\nZZZ = (AAA, AAA, AAA)\nXXX = (XXX, XXX)\n";
+        assert_eq!(solve_part2(input)?, 2);
         Ok(())
     }
 
     #[test]
-    // #[ignore]
-    fn test_part1_in() -> Result<()> {
-        assert_eq!(part1("day08_p1_in")?, 16409);
+    fn test_node_matchers() -> Result<()> {
+        let exact = "AAA".parse::<NodeMatcher>()?;
+        assert!(exact.matches("AAA") && !exact.matches("BAAA"));
+        let suffix = "*Z".parse::<NodeMatcher>()?;
+        assert!(suffix.matches("11Z") && !suffix.matches("Z1"));
+        let regex = "/^1.Z$/".parse::<NodeMatcher>()?;
+        assert!(regex.matches("12Z") && !regex.matches("112Z"));
+        assert_eq!(regex.to_string(), "/^1.Z$/");
+        assert!("A-A".parse::<NodeMatcher>().is_err());
+        assert!("/(/".parse::<NodeMatcher>().is_err());
         Ok(())
     }
-    //
+
     #[test]
-    fn test_part2_ex() -> Result<()> {
-        assert_eq!(part2("day08_p2_ex")?, 6);
+    fn test_long_and_numeric_ids() -> Result<()> {
+        let input = "LR\n\nSTART = (1, 22)\n1 = (END, END)\n22 = (END, END)\nEND = (END, END)\n";
+        let network = Network::parse(input)?;
+        let ends = Ends {
+            start: "START".parse()?,
+            end: "/^E/".parse()?,
+        };
+        let path = network.path(&ends)?;
+        assert_eq!(
+            path.iter().map(|&id| network.name(id)).collect::<Vec<_>>(),
+            ["START", "1", "END"]
+        );
+        assert!(network.path(&Ends::camel()).is_err());
         Ok(())
     }
-    //
+
     #[test]
-    #[ignore]
-    fn test_part2_in() -> Result<()> {
-        assert_eq!(part2("day08_p1_in")?, 0);
+    fn test_instruction_extensions() -> Result<()> {
+        let input =
+            "2L2S2\n\nAAA = (AAA, BBB, CCC)\nBBB = (ZZZ, BBB)\nCCC = (ZZZ)\nZZZ = (ZZZ, ZZZ)\n";
+        let network = Network::parse(input)?;
+        assert_eq!(network.instructions.0.iter().join(""), "LLSS2");
+        let path = network.path(&Ends::camel())?;
+        assert_eq!(
+            path.iter().map(|&id| network.name(id)).collect::<Vec<_>>(),
+            ["AAA", "AAA", "AAA", "AAA", "AAA", "CCC", "ZZZ"]
+        );
+
+        let err = Network::parse("LRX\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\n").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (1, 3));
+        assert_eq!(err.expected, INSTRUCTION);
+
+        let err =
+            Network::parse("LR999999999999L\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\n").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (1, 3));
+        assert_eq!(err.expected, "a run of at most 1000 instructions");
+        assert_eq!(err.snippet, "999999999999L");
+
+        let network = Network::parse("2\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\n")?;
+        let err = network.path(&Ends::camel()).unwrap_err();
+        assert_eq!(err.to_string(), "Node AAA has no child 2 to follow `2`");
         Ok(())
     }
 }
@@ -0,0 +1,168 @@
+use std::io::Write;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::{first_common_end, Cycle, Ends, Network};
+use crate::graph::NodeId;
+use crate::output::Format;
+
+/// A ghost's visit to an end node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EndHit {
+    pub node: String,
+    pub step: i64,
+    /// Whether the hit comes around again every cycle, rather than once before it.
+    pub repeats: bool,
+}
+
+/// The cycle a ghost falls into from one start node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CycleReport {
+    pub start: String,
+    pub prefix: i64,
+    pub length: i64,
+    pub ends: Vec<EndHit>,
+    /// Whether this start fits the LCM shortcut, see [`Cycle::is_clean`].
+    pub clean: bool,
+}
+
+/// Every start's cycle, and whether the LCM of their lengths can be trusted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Analysis {
+    pub starts: Vec<CycleReport>,
+    pub lcm_shortcut: bool,
+    pub first_common_end: Option<i64>,
+}
+
+/// Nodes visited from `start`, one per step and starting with it.
+fn walk(network: &Network, start: NodeId, steps: i64) -> Result<Vec<NodeId>> {
+    let mut nodes = vec![start];
+    let mut current = start;
+    for &instruction in network.instructions.0.iter().cycle().take(steps as usize) {
+        current = network.step(current, instruction)?;
+        nodes.push(current);
+    }
+    Ok(nodes)
+}
+
+pub fn analyse(network: &Network, ends: &Ends) -> Result<Analysis> {
+    let mut starts = vec![];
+    let mut cycles = vec![];
+    for (start, cycle) in network.cycles(ends)? {
+        let nodes = walk(network, start, cycle.prefix + cycle.length)?;
+        starts.push(CycleReport {
+            start: network.name(start).to_string(),
+            prefix: cycle.prefix,
+            length: cycle.length,
+            ends: cycle
+                .ends
+                .iter()
+                .map(|&step| EndHit {
+                    node: network.name(nodes[step as usize]).to_string(),
+                    step,
+                    repeats: step >= cycle.prefix,
+                })
+                .collect(),
+            clean: cycle.is_clean(),
+        });
+        cycles.push(cycle);
+    }
+
+    Ok(Analysis {
+        lcm_shortcut: cycles.iter().all(Cycle::is_clean),
+        first_common_end: first_common_end(&cycles),
+        starts,
+    })
+}
+
+/// `ZZZ@6` for a repeating hit, `(ZZZ@2)` for one before the cycle.
+fn ends(report: &CycleReport) -> String {
+    report
+        .ends
+        .iter()
+        .map(|end| match end.repeats {
+            true => format!("{}@{}", end.node, end.step),
+            false => format!("({}@{})", end.node, end.step),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+pub fn write(format: Format, analysis: &Analysis, out: &mut impl Write) -> Result<()> {
+    match format {
+        Format::Plain => {
+            writeln!(
+                out,
+                "{:<8} {:>8} {:>8}  {:<5}  ends",
+                "start", "prefix", "length", "clean"
+            )?;
+            for report in &analysis.starts {
+                writeln!(
+                    out,
+                    "{:<8} {:>8} {:>8}  {:<5}  {}",
+                    report.start,
+                    report.prefix,
+                    report.length,
+                    yes_no(report.clean),
+                    ends(report)
+                )?;
+            }
+            writeln!(out, "LCM shortcut: {}", yes_no(analysis.lcm_shortcut))?;
+            match analysis.first_common_end {
+                Some(step) => writeln!(out, "First common end: {}", step)?,
+                None => writeln!(out, "First common end: never")?,
+            }
+        }
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *out, analysis)?;
+            writeln!(out)?;
+        }
+        Format::Csv => {
+            writeln!(out, "start,prefix,length,clean,ends")?;
+            for report in &analysis.starts {
+                writeln!(
+                    out,
+                    "{},{},{},{},{}",
+                    report.start,
+                    report.prefix,
+                    report.length,
+                    report.clean,
+                    ends(report)
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Solution;
+
+    #[test]
+    fn test_analyse() -> Result<()> {
+        let network = Network::parse(&std::fs::read_to_string("day08_p2_ex")?)?;
+        let analysis = analyse(&network, &Ends::ghosts())?;
+        assert_eq!(analysis.starts.len(), 2);
+        assert!(analysis.starts[0].clean);
+        assert_eq!(
+            (analysis.starts[1].prefix, analysis.starts[1].length),
+            (1, 6)
+        );
+        assert_eq!(ends(&analysis.starts[1]), "22Z@3 22Z@6");
+        assert!(!analysis.lcm_shortcut);
+        assert_eq!(analysis.first_common_end, Some(6));
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::HashSet;
+use std::fmt::Write as _;
+
+use anyhow::Result;
+
+use super::{Ends, Network};
+use crate::graph::NodeId;
+
+const START: &str = "palegreen";
+const END: &str = "lightcoral";
+const PATH: &str = "blue";
+
+/// Writes the network as a Graphviz digraph, with `L` and `R` labelled edges (child
+/// digits for nodes with more children) and the nodes matching `ends` filled in.
+/// Edges taken by the instructions along `path` are drawn thick and blue.
+pub fn dot(network: &Network, ends: &Ends, path: Option<&[NodeId]>) -> Result<String> {
+    let taken = path
+        .unwrap_or_default()
+        .windows(2)
+        .map(|step| step[0])
+        .zip(network.instructions.0.iter().cycle())
+        .filter_map(|(id, instruction)| Some((id, instruction.child()?)))
+        .collect::<HashSet<_>>();
+    let is_start = network.mask(&ends.start);
+    let is_end = network.mask(&ends.end);
+
+    let mut dot = String::new();
+    writeln!(dot, "digraph day08 {{")?;
+    writeln!(dot, "    node [shape=box, fontname=monospace];")?;
+    for id in network.graph.nodes() {
+        let fill = if is_start[id] {
+            Some(START)
+        } else if is_end[id] {
+            Some(END)
+        } else {
+            None
+        };
+        if let Some(fill) = fill {
+            writeln!(
+                dot,
+                "    \"{}\" [style=filled, fillcolor={}];",
+                network.name(id),
+                fill
+            )?;
+        }
+    }
+    for id in network.graph.nodes() {
+        let children = network.graph.edges(id);
+        for (child, &(to, _)) in children.iter().enumerate() {
+            let label = match (children.len(), child) {
+                (..=2, 0) => "L".to_string(),
+                (..=2, 1) => "R".to_string(),
+                _ => child.to_string(),
+            };
+            write!(
+                dot,
+                "    \"{}\" -> \"{}\" [label={}",
+                network.name(id),
+                network.name(to),
+                label
+            )?;
+            if taken.contains(&(id, child)) {
+                write!(dot, ", color={}, penwidth=2", PATH)?;
+            }
+            writeln!(dot, "];")?;
+        }
+    }
+    writeln!(dot, "}}")?;
+
+    Ok(dot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Solution;
+
+    #[test]
+    fn test_dot() -> Result<()> {
+        let network = Network::parse(&std::fs::read_to_string("day08_p1_ex2")?)?;
+        let path = network.path(&Ends::camel())?;
+        let dot = dot(&network, &Ends::ghosts(), Some(&path))?;
+        assert!(dot.starts_with("digraph day08 {\n"));
+        assert!(dot.contains("    \"AAA\" [style=filled, fillcolor=palegreen];\n"));
+        assert!(dot.contains("    \"ZZZ\" -> \"ZZZ\" [label=L];\n"));
+        assert!(dot.contains("    \"AAA\" -> \"BBB\" [label=L, color=blue, penwidth=2];\n"));
+        assert!(dot.contains("    \"BBB\" -> \"ZZZ\" [label=R, color=blue, penwidth=2];\n"));
+        assert_eq!(dot.matches("->").count(), 6);
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+use super::Network;
+use crate::graph::NodeId;
+
+/// Where every node leads after any number of steps, by binary lifting over whole
+/// passes of the instructions.
+///
+/// A walk that has to follow a child its node does not have leads nowhere, which only
+/// matters once a walk actually gets there.
+#[derive(Debug, Clone)]
+pub struct JumpTable {
+    nodes: usize,
+    pass: usize,
+    /// `within[r * nodes + id]` is where `id` is `r` steps into a pass.
+    within: Vec<Option<NodeId>>,
+    /// `lifts[k][id]` is where `id` is after `2^k` passes.
+    lifts: Vec<Vec<Option<NodeId>>>,
+}
+
+impl JumpTable {
+    pub fn new(network: &Network) -> Self {
+        let nodes = network.graph.len();
+        let pass = network.instructions.0.len();
+
+        let mut within = network.graph.nodes().map(Some).collect::<Vec<_>>();
+        for (r, &instruction) in network.instructions.0.iter().enumerate() {
+            for id in network.graph.nodes() {
+                let next = within[r * nodes + id]
+                    .and_then(|current| network.step(current, instruction).ok());
+                within.push(next);
+            }
+        }
+
+        let mut lifts = vec![within[pass * nodes..].to_vec()];
+        within.truncate(pass * nodes);
+        for k in 1..u64::BITS as usize {
+            let last = &lifts[k - 1];
+            lifts.push(last.iter().map(|&id| last[id?]).collect());
+        }
+
+        Self {
+            nodes,
+            pass,
+            within,
+            lifts,
+        }
+    }
+
+    /// Where `id` is after one full pass of the instructions.
+    pub fn after_pass(&self, id: NodeId) -> Option<NodeId> {
+        self.lifts[0][id]
+    }
+
+    /// Where a walk from `id` is after `steps` steps, in `O(log steps)`.
+    pub fn at(&self, id: NodeId, steps: u64) -> Option<NodeId> {
+        let (mut passes, rest) = (steps / self.pass as u64, steps % self.pass as u64);
+        let mut id = id;
+        for lift in &self.lifts {
+            if passes == 0 {
+                break;
+            }
+            if passes & 1 == 1 {
+                id = lift[id]?;
+            }
+            passes >>= 1;
+        }
+        self.within[rest as usize * self.nodes + id]
+    }
+
+    /// Where each of `starts` is after `steps` steps.
+    pub fn all_at<'a>(
+        &'a self,
+        starts: &'a [NodeId],
+        steps: u64,
+    ) -> impl Iterator<Item = Option<NodeId>> + 'a {
+        starts.iter().map(move |&id| self.at(id, steps))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day08::Ends;
+    use crate::Solution;
+    use anyhow::Result;
+
+    #[test]
+    fn test_matches_walking() -> Result<()> {
+        let network = Network::parse(&std::fs::read_to_string("day08_p2_ex")?)?;
+        let table = JumpTable::new(&network);
+        for start in network.graph.nodes() {
+            let mut id = start;
+            let instructions = network.instructions.0.iter().cycle();
+            for (steps, &instruction) in instructions.enumerate().take(20) {
+                assert_eq!(table.at(start, steps as u64), Some(id));
+                id = network.step(id, instruction)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_huge_step_counts() -> Result<()> {
+        let network = Network::parse(&std::fs::read_to_string("day08_p2_ex")?)?;
+        let table = JumpTable::new(&network);
+        let starts = network.matching(&Ends::ghosts().start).collect::<Vec<_>>();
+        let is_end = network.mask(&Ends::ghosts().end);
+        let all_end = |steps| {
+            table
+                .all_at(&starts, steps)
+                .all(|id| id.is_some_and(|id| is_end[id]))
+        };
+        // Both ghosts are on a `Z` node every 6 steps, and only then.
+        assert!(all_end(6_000_000_000));
+        assert!(!all_end(6_000_000_001));
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_child_only_stops_walks_reaching_it() -> Result<()> {
+        let input = "L2\n\nAAA = (BBB, BBB, BBB)\nBBB = (ZZZ, ZZZ, ZZZ)\nZZZ = (AAA, AAA, AAA)\nXXX = (XXX, XXX)\n";
+        let network = Network::parse(input)?;
+        let table = JumpTable::new(&network);
+        let id = |name: &str| network.graph.id(&name.to_string()).unwrap();
+        let (aaa, zzz, xxx) = (id("AAA"), id("ZZZ"), id("XXX"));
+        // XXX has no child 2, which only stops the walk from XXX itself.
+        assert_eq!(table.at(aaa, 2), Some(zzz));
+        assert_eq!(table.at(aaa, 1_000_000_001), Some(zzz));
+        assert_eq!(table.at(xxx, 1), Some(xxx));
+        assert_eq!(table.at(xxx, 2), None);
+        assert_eq!(table.after_pass(xxx), None);
+        Ok(())
+    }
+}
@@ -21,6 +21,13 @@ use std::{
     str::FromStr,
 };
 
+use log::trace;
+
+use crate::error::ParseError;
+use crate::Solution;
+
+const DAY: u32 = 9;
+
 struct SingleLinkedNode<T> {
     next: *mut SingleLinkedNode<T>,
     value: T,
@@ -56,7 +63,7 @@ impl FromIterator<i64> for SingleLinkedList<i64> {
             }));
             let mut current = unsafe { &mut *current.head };
 
-            while let Some(value) = iter.next() {
+            for value in iter {
                 current.next = Box::into_raw(Box::new(SingleLinkedNode {
                     next: std::ptr::null_mut(),
                     value,
@@ -83,11 +90,11 @@ impl Iterator for SingleLinkedList<i64> {
 }
 
 #[derive(Debug)]
-struct Pyramid {
-    data: Vec<Vec<i64>>,
+pub struct Pyramid {
+    pub data: Vec<Vec<i64>>,
 }
 impl Pyramid {
-    fn new(seq: impl IntoIterator<Item = i64>) -> Self {
+    pub fn new(seq: impl IntoIterator<Item = i64>) -> Self {
         let iter = seq.into_iter();
         let first_row = iter.collect::<Vec<_>>();
         let mut data = Vec::new();
@@ -99,16 +106,13 @@ impl Pyramid {
         }
         Self { data }
     }
-    fn first_row(&self) -> &[i64] {
-        &self.data[0]
-    }
-    fn extrapolated_value(&self) -> i64 {
+    pub fn extrapolated_value(&self) -> i64 {
         *self.data[0].last().unwrap()
     }
-    fn extrapolated_value_front(&self) -> i64 {
+    pub fn extrapolated_value_front(&self) -> i64 {
         self.data[0][0]
     }
-    fn extrapolate(&mut self) {
+    pub fn extrapolate(&mut self) {
         let len = self.data.len() as i64;
         let mut idx: i64 = self.data.len() as i64 - 1;
         while idx >= 0 {
@@ -127,7 +131,7 @@ impl Pyramid {
             idx -= 1;
         }
     }
-    fn extrapolate_front(&mut self) {
+    pub fn extrapolate_front(&mut self) {
         let len = self.data.len() as i64;
         let mut idx: i64 = self.data.len() as i64 - 1;
         while idx >= 0 {
@@ -148,71 +152,90 @@ impl Pyramid {
     }
 }
 
-fn part1(input: &'static str) -> Result<i64> {
-    let file_contents = std::fs::read_to_string(input).expect("Could not read file");
-    let lines = file_contents.lines();
-    let entries = lines
-        .map(|line| {
-            line
-                .split(' ')
-                .map(|x| x.parse::<i64>().unwrap())
-                .collect::<SingleLinkedList<_>>()
-        });
-
-    let mut pyramids = entries.map(|entry| Pyramid::new(entry)).collect::<Vec<_>>();
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub histories: Vec<Vec<i64>>,
+}
 
-    pyramids.iter().for_each(|pyramid| {
-        for row in pyramid.data.iter() {
-            println!("{:?}", row);
-        }
-    });
+impl Report {
+    fn pyramids(&self) -> Vec<Pyramid> {
+        let entries = self
+            .histories
+            .iter()
+            .map(|history| history.iter().copied().collect::<SingleLinkedList<_>>());
 
-    pyramids.iter_mut().for_each(|pyramid| pyramid.extrapolate());
+        let pyramids = entries.map(Pyramid::new).collect::<Vec<_>>();
 
-    pyramids.iter().for_each(|pyramid| {
-        for row in pyramid.data.iter() {
-            println!("{:?}", row);
-        }
-    });
+        pyramids.iter().for_each(|pyramid| {
+            for row in pyramid.data.iter() {
+                trace!("{:?}", row);
+            }
+        });
 
-    Ok(pyramids.iter().map(|pyramid| pyramid.extrapolated_value()).sum::<i64>() as i64)
+        pyramids
+    }
 }
 
-fn part2(input: &'static str) -> Result<i64> {
-    let file_contents = std::fs::read_to_string(input).expect("Could not read file");
-    let lines = file_contents.lines();
-    let entries = lines
-        .map(|line| {
-            line
-                .split(' ')
-                .map(|x| x.parse::<i64>().unwrap())
-                .collect::<SingleLinkedList<_>>()
+impl Solution for Report {
+    type Input = Report;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let lines = input.lines();
+        let histories = lines
+            .enumerate()
+            .map(|(line_idx, line)| {
+                let mut offset = 0;
+                line
+                    .split(' ')
+                    .map(|x| {
+                        let start = offset;
+                        offset += x.len() + 1;
+                        x.parse::<i64>().map_err(|_| {
+                            ParseError::new(DAY, line_idx + 1, start + 1, "integer", &line[start..])
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Report { histories })
+    }
+
+    fn part1(input: &Self::Input) -> Result<i64> {
+        let mut pyramids = input.pyramids();
+
+        pyramids.iter_mut().for_each(|pyramid| pyramid.extrapolate());
+
+        pyramids.iter().for_each(|pyramid| {
+            for row in pyramid.data.iter() {
+                trace!("{:?}", row);
+            }
         });
 
-    let mut pyramids = entries.map(|entry| Pyramid::new(entry)).collect::<Vec<_>>();
+        Ok(pyramids.iter().map(|pyramid| pyramid.extrapolated_value()).sum::<i64>())
+    }
 
-    pyramids.iter().for_each(|pyramid| {
-        for row in pyramid.data.iter() {
-            println!("{:?}", row);
-        }
-    });
+    fn part2(input: &Self::Input) -> Result<i64> {
+        let mut pyramids = input.pyramids();
 
-    pyramids.iter_mut().for_each(|pyramid| pyramid.extrapolate_front());
+        pyramids.iter_mut().for_each(|pyramid| pyramid.extrapolate_front());
 
-    pyramids.iter().for_each(|pyramid| {
-        for row in pyramid.data.iter() {
-            println!("{:?}", row);
-        }
-    });
+        pyramids.iter().for_each(|pyramid| {
+            for row in pyramid.data.iter() {
+                trace!("{:?}", row);
+            }
+        });
+
+        Ok(pyramids.iter().map(|pyramid| pyramid.extrapolated_value_front()).sum::<i64>())
+    }
+}
 
-    Ok(pyramids.iter().map(|pyramid| pyramid.extrapolated_value_front()).sum::<i64>() as i64)
+pub fn solve_part1(input: &str) -> Result<i64> {
+    Report::solve_part1(input)
 }
 
-fn main() -> Result<()> {
-    // println!("Part 1: {}", part1("day08_p1_in")?);
-    // println!("Part 2: {}", part2("day08_p1_in")?);
-    println!("Part 2: {}", part2("day08_p1_in")?);
-    Ok(())
+pub fn solve_part2(input: &str) -> Result<i64> {
+    Report::solve_part2(input)
 }
 
 #[cfg(test)]
@@ -220,26 +243,10 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_part1_ex() -> Result<()> {
-        assert_eq!(part1("day09_p1_ex")?, 114);
-        Ok(())
-    }
-
-    #[test]
-    fn test_part1_in() -> Result<()> {
-        assert_eq!(part1("day09_p1_in")?, 2174807968);
-        Ok(())
-    }
-    //
-    #[test]
-    fn test_part2_ex() -> Result<()> {
-        assert_eq!(part2("day09_p1_ex")?, 2);
-        Ok(())
-    }
-    //
-    #[test]
-    fn test_part2_in() -> Result<()> {
-        assert_eq!(part2("day09_p1_in")?, 1208);
-        Ok(())
+    fn test_parse_error() {
+        let err = solve_part1("0 3 6\n1 x 3\n").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.snippet, "x 3");
     }
 }
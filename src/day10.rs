@@ -4,6 +4,7 @@
 use anyhow::{anyhow, Result};
 use contracts::*;
 use indicatif::{ProgressBar, ProgressStyle};
+use itertools::Itertools;
 use nom::{
     bytes::complete::{tag, take, take_until},
     character::complete::{char, digit1, line_ending, space0, space1},
@@ -21,96 +22,341 @@ use std::{
     str::FromStr,
 };
 
-#[derive(Debug)]
-struct Pipe {
-    id: usize,
-    left: Option<usize>,
-    right: Option<usize>,
-    up: Option<usize>,
-    down: Option<usize>,
+use log::debug;
+
+use crate::error::ParseError;
+use crate::graph::{Graph, NodeId};
+use crate::grid::{Dir, Grid, Pos};
+use crate::Solution;
+
+pub mod render;
+
+const DAY: u32 = 10;
+
+/// A tile of the maze, named after the directions it connects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pipe {
+    Vertical,
+    Horizontal,
+    UpRight,
+    UpLeft,
+    DownLeft,
+    DownRight,
+    Ground,
+    Start,
 }
 
 impl Pipe {
-    fn new(id: usize) -> Self {
-        Self {
-            id,
-            left: None,
-            right: None,
-            up: None,
-            down: None,
+    pub fn from_char(c: char) -> Option<Self> {
+        Some(match c {
+            '|' => Pipe::Vertical,
+            '-' => Pipe::Horizontal,
+            'L' => Pipe::UpRight,
+            'J' => Pipe::UpLeft,
+            '7' => Pipe::DownLeft,
+            'F' => Pipe::DownRight,
+            '.' => Pipe::Ground,
+            'S' => Pipe::Start,
+            _ => return None,
+        })
+    }
+
+    /// The directions this pipe leads to, all of them for the start tile.
+    pub fn connections(self) -> &'static [Dir] {
+        match self {
+            Pipe::Vertical => &[Dir::Up, Dir::Down],
+            Pipe::Horizontal => &[Dir::Left, Dir::Right],
+            Pipe::UpRight => &[Dir::Up, Dir::Right],
+            Pipe::UpLeft => &[Dir::Up, Dir::Left],
+            Pipe::DownLeft => &[Dir::Down, Dir::Left],
+            Pipe::DownRight => &[Dir::Down, Dir::Right],
+            Pipe::Ground => &[],
+            Pipe::Start => &Dir::ALL,
         }
     }
+
+    pub fn connects(self, dir: Dir) -> bool {
+        self.connections().contains(&dir)
+    }
+
+    /// The pipe leading exactly to `dirs`, if there is one.
+    pub fn joining(dirs: &[Dir]) -> Option<Self> {
+        [
+            Pipe::Vertical,
+            Pipe::Horizontal,
+            Pipe::UpRight,
+            Pipe::UpLeft,
+            Pipe::DownLeft,
+            Pipe::DownRight,
+        ]
+        .into_iter()
+        .find(|pipe| dirs.len() == 2 && dirs.iter().all(|&dir| pipe.connects(dir)))
+    }
 }
 
-fn part1(input: &'static str) -> Result<i64> {
-    let file_contents = std::fs::read_to_string(input).expect("Could not read file");
-    let line_len = file_contents.lines().next().unwrap().len();
-
-    // Possible pipes
-    // -
-    // |
-    // L
-    // J
-    // F
-    // 7
-    // Ground
-    // . (dot) [Same as pipe with no connections]
-    let mut pipes = file_contents
-        .lines()
-        .enumerate()
-        .map(|(y, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(x, c)| match c {
-                    '-' => Some(Pipe::new(x + y * line_len)),
-                    '|' => Some(Pipe::new(x + y * line_len)),
-                    'L' => Some(Pipe::new(x + y * line_len)),
-                    'J' => Some(Pipe::new(x + y * line_len)),
-                    'F' => Some(Pipe::new(x + y * line_len)),
-                    '7' => Some(Pipe::new(x + y * line_len)),
-                    '.' => Some(Pipe::new(x + y * line_len)),
-                    _ => None,
-                })
-                .collect::<Vec<_>>()
-        })
-        .flatten()
-        .filter_map(|p| p)
-        .collect::<Vec<_>>();
-
-    // Connect pipes
-    for y in 0..line_len {
-        for x in 0..line_len {
-            let i = x + y * line_len;
-            let mut pipe = pipes.get_mut(i).unwrap();
-            if let Some(p) = pipes.get(i + 1) {
-                pipe.right = Some(p.id);
-            }
-            if let Some(p) = pipes.get(i - 1) {
-                pipe.left = Some(p.id);
+impl Display for Pipe {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            Pipe::Vertical => '|',
+            Pipe::Horizontal => '-',
+            Pipe::UpRight => 'L',
+            Pipe::UpLeft => 'J',
+            Pipe::DownLeft => '7',
+            Pipe::DownRight => 'F',
+            Pipe::Ground => '.',
+            Pipe::Start => 'S',
+        };
+        write!(f, "{}", c)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+    Loop,
+    Inside,
+    Outside,
+}
+
+/// Ways of counting the tiles enclosed by the main loop, which must all agree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Enclosure {
+    /// Walks each row, toggling inside/outside on loop tiles that lead up.
+    Scanline,
+    /// Area of the loop polygon by the shoelace formula, less its boundary by Pick's theorem.
+    #[default]
+    Shoelace,
+    /// Floods the outside of the maze drawn at twice its size, so it can squeeze between pipes.
+    FloodFill,
+}
+
+#[derive(Debug, Clone)]
+pub struct Maze {
+    pub pipes: Grid<Pipe>,
+    pub start: Pos,
+    /// Tiles linked to the neighbours whose pipes connect back to them.
+    pub graph: Graph<Pos>,
+}
+
+/// One node per tile, row by row, with an edge for each pair of pipes that connect.
+fn pipe_graph(pipes: &Grid<Pipe>) -> Graph<Pos> {
+    let mut graph = Graph::new();
+    for pos in pipes.positions() {
+        graph.add_node(pos);
+    }
+    for (id, pos) in pipes.positions().enumerate() {
+        for &dir in pipes[pos].connections() {
+            let next = pipes
+                .step(pos, dir)
+                .filter(|&next| pipes[next].connects(dir.opposite()));
+            if let Some(next) = next {
+                graph.add_edge(id, next.0 + next.1 * pipes.width(), ());
             }
-            if let Some(p) = pipes.get(i + line_len) {
-                pipe.down = Some(p.id);
+        }
+    }
+    graph
+}
+
+impl Maze {
+    fn node(&self, pos: Pos) -> NodeId {
+        pos.0 + pos.1 * self.pipes.width()
+    }
+
+    /// Neighbours of `pos` whose pipes connect back to it.
+    pub fn connected(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.graph
+            .neighbours(self.node(pos))
+            .map(|id| *self.graph.label(id))
+    }
+
+    /// Positions of the loop through the start tile, in order and starting there.
+    pub fn main_loop(&self) -> Result<Vec<Pos>> {
+        let start = self.node(self.start);
+        let mut tiles = vec![self.start];
+        let (mut previous, mut id) = (start, start);
+        loop {
+            let next = self
+                .graph
+                .neighbours(id)
+                .find(|&next| next != previous)
+                .ok_or_else(|| anyhow!("Dead end at {:?}", self.graph.label(id)))?;
+            (previous, id) = (id, next);
+            if id == start {
+                break;
             }
-            if let Some(p) = pipes.get(i - line_len) {
-                pipe.up = Some(p.id);
+            tiles.push(*self.graph.label(id));
+        }
+        debug!("main loop of {} tiles", tiles.len());
+
+        Ok(tiles)
+    }
+
+    /// Number of tiles enclosed by the main loop.
+    pub fn enclosed(&self, method: Enclosure) -> Result<i64> {
+        let tiles = self.main_loop()?;
+        let enclosed = match method {
+            Enclosure::Scanline => self.enclosed_scanline(&tiles),
+            Enclosure::Shoelace => Self::enclosed_shoelace(&tiles),
+            Enclosure::FloodFill => self.enclosed_flood_fill(&tiles),
+        };
+        debug!("{:?}: {} enclosed tiles", method, enclosed);
+
+        Ok(enclosed)
+    }
+
+    /// Where every tile lies relative to the main loop.
+    pub fn regions(&self) -> Result<Grid<Region>> {
+        Ok(self.regions_of(&self.main_loop()?))
+    }
+
+    fn regions_of(&self, tiles: &[Pos]) -> Grid<Region> {
+        let mut regions = Grid::filled(self.pipes.width(), self.pipes.height(), Region::Outside);
+        for &pos in tiles {
+            regions[pos] = Region::Loop;
+        }
+
+        for y in 0..self.pipes.height() {
+            let mut inside = false;
+            for x in 0..self.pipes.width() {
+                if regions[(x, y)] == Region::Loop {
+                    // Only pipes leading up count, so `L-7` crosses the row once and `L-J` never.
+                    inside ^= self.pipes[(x, y)].connects(Dir::Up);
+                } else if inside {
+                    regions[(x, y)] = Region::Inside;
+                }
             }
         }
+        regions
+    }
+
+    fn enclosed_scanline(&self, tiles: &[Pos]) -> i64 {
+        self.regions_of(tiles)
+            .iter()
+            .filter(|(_, region)| **region == Region::Inside)
+            .count() as i64
     }
 
-    Ok(0)
+    fn enclosed_shoelace(tiles: &[Pos]) -> i64 {
+        let twice_area = tiles
+            .iter()
+            .zip(tiles.iter().cycle().skip(1))
+            .map(|(&(x1, y1), &(x2, y2))| x1 as i64 * y2 as i64 - x2 as i64 * y1 as i64)
+            .sum::<i64>()
+            .abs();
+        // Pick's theorem: area = interior + boundary / 2 - 1
+        twice_area / 2 - tiles.len() as i64 / 2 + 1
+    }
+
+    fn enclosed_flood_fill(&self, tiles: &[Pos]) -> i64 {
+        // Tile (x, y) becomes cell (2x + 1, 2y + 1), pipes between tiles fill the cells in
+        // between, and a one cell border lets the flood reach all of the outside.
+        let scaled = |(x, y): Pos| (2 * x + 1, 2 * y + 1);
+        let mut wall = Grid::filled(
+            2 * self.pipes.width() + 1,
+            2 * self.pipes.height() + 1,
+            false,
+        );
+        for &pos in tiles {
+            let cell = scaled(pos);
+            wall[cell] = true;
+            for &dir in self.pipes[pos].connections() {
+                if let Some(between) = wall.step(cell, dir) {
+                    wall[between] = true;
+                }
+            }
+        }
+
+        let mut outside = Grid::filled(wall.width(), wall.height(), false);
+        let mut queue = VecDeque::from([(0, 0)]);
+        outside[(0, 0)] = true;
+        while let Some(cell) = queue.pop_front() {
+            for next in wall.neighbours4(cell) {
+                if !wall[next] && !outside[next] {
+                    outside[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        self.pipes
+            .positions()
+            .map(scaled)
+            .filter(|&cell| !wall[cell] && !outside[cell])
+            .count() as i64
+    }
 }
 
-fn part2(input: &'static str) -> Result<i64> {
-    let _file_contents = std::fs::read_to_string(input).expect("Could not read file");
+impl Solution for Maze {
+    type Input = Maze;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let mut pipes = Grid::parse(DAY, input, "pipe", Pipe::from_char)?;
+        let start = pipes
+            .iter()
+            .find(|(_, pipe)| **pipe == Pipe::Start)
+            .map(|(pos, _)| pos)
+            .ok_or_else(|| ParseError::at_offset(DAY, input, input.len(), "start tile `S`"))?;
+
+        // `S` takes the shape joining two of the neighbours that lead into it,
+        // picking the one whose loop closes back on `S`.
+        let dirs = Dir::ALL
+            .into_iter()
+            .filter(|&dir| {
+                pipes
+                    .step(start, dir)
+                    .is_some_and(|next| pipes[next].connects(dir.opposite()))
+            })
+            .collect::<Vec<_>>();
+        let shapes = dirs
+            .iter()
+            .tuple_combinations()
+            .filter_map(|(&a, &b)| Pipe::joining(&[a, b]))
+            .collect::<Vec<_>>();
+        for shape in shapes {
+            pipes[start] = shape;
+            let graph = pipe_graph(&pipes);
+            let maze = Maze {
+                pipes,
+                start,
+                graph,
+            };
+            if maze.main_loop().is_ok() {
+                debug!("start tile {:?} is {:?}", start, shape);
+                return Ok(maze);
+            }
+            pipes = maze.pipes;
+        }
+
+        pipes[start] = Pipe::Start;
+        let rest = pipes.row(start.1).unwrap_or_default()[start.0..]
+            .iter()
+            .map(Pipe::to_string)
+            .collect::<String>();
+        Err(ParseError::new(
+            DAY,
+            start.1 + 1,
+            start.0 + 1,
+            "`S` on a loop of pipes",
+            &rest,
+        )
+        .into())
+    }
 
-    Ok(0)
+    /// The farthest point along the loop is halfway around it.
+    fn part1(input: &Self::Input) -> Result<i64> {
+        Ok(input.main_loop()?.len() as i64 / 2)
+    }
+
+    fn part2(input: &Self::Input) -> Result<i64> {
+        input.enclosed(Enclosure::default())
+    }
+}
+
+pub fn solve_part1(input: &str) -> Result<i64> {
+    Maze::solve_part1(input)
 }
 
-fn main() -> Result<()> {
-    println!("Part 1: {}", part1("day10_p1_in")?);
-    // println!("Part 2: {}", part2("day08_p1_in")?);
-    // println!("Part 2: {}", part2("day08_p1_in")?);
-    Ok(())
+pub fn solve_part2(input: &str) -> Result<i64> {
+    Maze::solve_part2(input)
 }
 
 #[cfg(test)]
@@ -118,29 +364,61 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_part1_ex() -> Result<()> {
-        assert_eq!(part1("day10_p1_ex")?, 4);
+    fn test_parse_error() {
+        let err = solve_part1("S-7\n|x|\nL-J\n").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert_eq!(err.expected, "pipe");
+    }
+
+    #[test]
+    fn test_start_needs_two_pipes() {
+        let err = solve_part1("-S-\n.|.\n").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (1, 2));
+        assert_eq!(err.snippet, "S-");
+    }
+
+    #[test]
+    fn test_start_with_extra_neighbours() -> Result<()> {
+        assert_eq!(solve_part1(".|...\n-S-7.\n.|.|.\n.L-J.\n")?, 4);
         Ok(())
     }
 
+    fn enclosed(input: &str, method: Enclosure) -> Result<i64> {
+        Maze::parse(&std::fs::read_to_string(input)?)?.enclosed(method)
+    }
+
     #[test]
-    #[ignore]
-    fn test_part1_in() -> Result<()> {
-        assert_eq!(part1("day10_p1_in")?, 2174807968);
+    fn test_enclosed_scanline() -> Result<()> {
+        assert_eq!(enclosed("day10_p2_ex", Enclosure::Scanline)?, 4);
+        assert_eq!(enclosed("day10_p2_ex2", Enclosure::Scanline)?, 8);
+        assert_eq!(enclosed("day10_p2_ex3", Enclosure::Scanline)?, 10);
         Ok(())
     }
-    //
+
     #[test]
-    #[ignore]
-    fn test_part2_ex() -> Result<()> {
-        assert_eq!(part2("day10_p1_ex")?, 2);
+    fn test_enclosed_shoelace() -> Result<()> {
+        assert_eq!(enclosed("day10_p2_ex", Enclosure::Shoelace)?, 4);
+        assert_eq!(enclosed("day10_p2_ex2", Enclosure::Shoelace)?, 8);
+        assert_eq!(enclosed("day10_p2_ex3", Enclosure::Shoelace)?, 10);
         Ok(())
     }
-    //
+
+    #[test]
+    fn test_enclosed_flood_fill() -> Result<()> {
+        assert_eq!(enclosed("day10_p2_ex", Enclosure::FloodFill)?, 4);
+        assert_eq!(enclosed("day10_p2_ex2", Enclosure::FloodFill)?, 8);
+        assert_eq!(enclosed("day10_p2_ex3", Enclosure::FloodFill)?, 10);
+        Ok(())
+    }
+
     #[test]
-    #[ignore]
-    fn test_part2_in() -> Result<()> {
-        assert_eq!(part2("day10_p1_in")?, 1208);
+    fn test_connected() -> Result<()> {
+        let maze = Maze::parse(&std::fs::read_to_string("day10_p1_ex")?)?;
+        assert_eq!(maze.start, (1, 1));
+        assert_eq!(maze.pipes[maze.start], Pipe::DownRight);
+        assert_eq!(maze.connected((1, 1)).collect::<Vec<_>>(), [(1, 2), (2, 1)]);
         Ok(())
     }
 }
@@ -0,0 +1,222 @@
+use std::fmt::Write as _;
+use std::io::Write;
+
+use anyhow::Result;
+use clap::ValueEnum;
+
+use super::{Maze, Pipe, Region};
+use crate::grid::{Dir, Grid};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RenderFormat {
+    /// Heavy box drawing for the loop, `I` inside and `O` outside it
+    Plain,
+    /// Box drawing with the loop, inside and outside in ANSI colours
+    Ansi,
+    /// Vector image of the loop and the tiles it encloses
+    Svg,
+    /// Binary PPM image, three pixels per tile
+    Ppm,
+}
+
+const BOLD_YELLOW: &str = "\x1b[1;33m";
+const GREEN_BACKGROUND: &str = "\x1b[42m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+/// Pixels per tile in SVG output.
+const SVG_TILE: usize = 8;
+/// Pixels per tile in PPM output, odd so a pipe runs through the middle one.
+const PPM_TILE: usize = 3;
+
+type Rgb = [u8; 3];
+const OUTSIDE: Rgb = [255, 255, 255];
+const INSIDE: Rgb = [136, 204, 136];
+const LOOP: Rgb = [204, 102, 0];
+const JUNK: Rgb = [160, 160, 160];
+
+fn light(pipe: Pipe) -> char {
+    match pipe {
+        Pipe::Vertical => '│',
+        Pipe::Horizontal => '─',
+        Pipe::UpRight => '└',
+        Pipe::UpLeft => '┘',
+        Pipe::DownLeft => '┐',
+        Pipe::DownRight => '┌',
+        Pipe::Ground => '·',
+        Pipe::Start => 'S',
+    }
+}
+
+fn heavy(pipe: Pipe) -> char {
+    match pipe {
+        Pipe::Vertical => '┃',
+        Pipe::Horizontal => '━',
+        Pipe::UpRight => '┗',
+        Pipe::UpLeft => '┛',
+        Pipe::DownLeft => '┓',
+        Pipe::DownRight => '┏',
+        pipe => light(pipe),
+    }
+}
+
+/// Redraws a maze with its main loop and the regions inside and outside of it.
+pub struct Render<'a> {
+    maze: &'a Maze,
+    regions: Grid<Region>,
+}
+
+impl<'a> Render<'a> {
+    pub fn new(maze: &'a Maze) -> Result<Self> {
+        Ok(Self {
+            maze,
+            regions: maze.regions()?,
+        })
+    }
+
+    pub fn text(&self, ansi: bool) -> String {
+        let mut out = String::new();
+        for ((x, y), &pipe) in self.maze.pipes.iter() {
+            if x == 0 && y > 0 {
+                out.push('\n');
+            }
+            match (self.regions[(x, y)], ansi) {
+                (Region::Loop, false) => out.push(heavy(pipe)),
+                (Region::Inside, false) => out.push('I'),
+                (Region::Outside, false) => out.push('O'),
+                (region, true) => {
+                    let style = match region {
+                        Region::Loop => BOLD_YELLOW,
+                        Region::Inside => GREEN_BACKGROUND,
+                        Region::Outside => DIM,
+                    };
+                    out.push_str(style);
+                    out.push(light(pipe));
+                    out.push_str(RESET);
+                }
+            }
+        }
+        out.push('\n');
+        out
+    }
+
+    pub fn svg(&self) -> Result<String> {
+        let (width, height) = (self.maze.pipes.width(), self.maze.pipes.height());
+        let center = |v: usize| v * SVG_TILE + SVG_TILE / 2;
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}">"#,
+            width * SVG_TILE,
+            height * SVG_TILE
+        )?;
+        writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#)?;
+        for ((x, y), _) in self.regions.iter().filter(|(_, r)| **r == Region::Inside) {
+            writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" fill="rgb({},{},{})"/>"#,
+                x * SVG_TILE,
+                y * SVG_TILE,
+                SVG_TILE,
+                SVG_TILE,
+                INSIDE[0],
+                INSIDE[1],
+                INSIDE[2]
+            )?;
+        }
+        let points = self
+            .maze
+            .main_loop()?
+            .iter()
+            .map(|&(x, y)| format!("{},{}", center(x), center(y)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        writeln!(
+            svg,
+            r#"<polygon points="{}" fill="none" stroke="rgb({},{},{})" stroke-width="2"/>"#,
+            points, LOOP[0], LOOP[1], LOOP[2]
+        )?;
+        writeln!(svg, "</svg>")?;
+
+        Ok(svg)
+    }
+
+    pub fn ppm(&self) -> Vec<u8> {
+        let pixels = self.maze.pipes.width() * PPM_TILE;
+        let mut image = Grid::filled(pixels, self.maze.pipes.height() * PPM_TILE, OUTSIDE);
+
+        let middle = PPM_TILE / 2;
+        for ((x, y), &pipe) in self.maze.pipes.iter() {
+            let (background, foreground) = match self.regions[(x, y)] {
+                Region::Loop => (OUTSIDE, LOOP),
+                Region::Inside => (INSIDE, JUNK),
+                Region::Outside => (OUTSIDE, JUNK),
+            };
+            let mut paint = |(dx, dy): (usize, usize), rgb: Rgb| {
+                image[(x * PPM_TILE + dx, y * PPM_TILE + dy)] = rgb;
+            };
+            for dy in 0..PPM_TILE {
+                for dx in 0..PPM_TILE {
+                    paint((dx, dy), background);
+                }
+            }
+            // A line from the middle pixel to each edge the pipe leads to.
+            for &dir in pipe.connections() {
+                for i in 0..=middle {
+                    let pixel = match dir {
+                        Dir::Up => (middle, i),
+                        Dir::Right => (middle + i, middle),
+                        Dir::Down => (middle, middle + i),
+                        Dir::Left => (i, middle),
+                    };
+                    paint(pixel, foreground);
+                }
+            }
+        }
+
+        let mut ppm = format!("P6\n{} {}\n255\n", image.width(), image.height()).into_bytes();
+        ppm.extend(image.iter().flat_map(|(_, rgb)| *rgb));
+        ppm
+    }
+
+    pub fn write(&self, format: RenderFormat, out: &mut impl Write) -> Result<()> {
+        match format {
+            RenderFormat::Plain => out.write_all(self.text(false).as_bytes())?,
+            RenderFormat::Ansi => out.write_all(self.text(true).as_bytes())?,
+            RenderFormat::Svg => out.write_all(self.svg()?.as_bytes())?,
+            RenderFormat::Ppm => out.write_all(&self.ppm())?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Solution;
+
+    fn maze() -> Result<Maze> {
+        Maze::parse(&std::fs::read_to_string("day10_p2_ex")?)
+    }
+
+    #[test]
+    fn test_plain() -> Result<()> {
+        let maze = maze()?;
+        let text = Render::new(&maze)?.text(false);
+        assert_eq!(text.lines().nth(1), Some("O┏━━━━━━━┓O"));
+        assert_eq!(text.lines().nth(6), Some("O┃II┃O┃II┃O"));
+        assert_eq!(text.matches('I').count(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ppm_size() -> Result<()> {
+        let maze = maze()?;
+        let ppm = Render::new(&maze)?.ppm();
+        let header = "P6\n33 27\n255\n";
+        assert!(ppm.starts_with(header.as_bytes()));
+        assert_eq!(ppm.len(), header.len() + 33 * 27 * 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+use std::fmt::{Display, Formatter};
+
+use nom::error::ErrorKind;
+use pest::error::{ErrorVariant, LineColLocation};
+use pest::iterators::Pair;
+use pest::RuleType;
+
+/// Longest piece of offending input quoted in an error message.
+const SNIPPET_LEN: usize = 20;
+
+/// A malformed puzzle input, located by line and column (both 1-based).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u32,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub snippet: String,
+}
+
+impl ParseError {
+    pub fn new(
+        day: u32,
+        line: usize,
+        column: usize,
+        expected: impl Into<String>,
+        rest_of_line: &str,
+    ) -> Self {
+        Self {
+            day,
+            line,
+            column,
+            expected: expected.into(),
+            snippet: rest_of_line.trim_end().chars().take(SNIPPET_LEN).collect(),
+        }
+    }
+
+    /// Locates the byte `offset` into `input`.
+    pub fn at_offset(day: u32, input: &str, offset: usize, expected: impl Into<String>) -> Self {
+        let offset = offset.min(input.len());
+        let line_start = input[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line = input[..offset].matches('\n').count() + 1;
+        let column = input[line_start..offset].chars().count() + 1;
+        let rest = input[offset..].lines().next().unwrap_or("");
+
+        Self::new(day, line, column, expected, rest)
+    }
+
+    pub fn from_pest<R: RuleType>(day: u32, err: pest::error::Error<R>) -> Self {
+        let (line, column) = match err.line_col {
+            LineColLocation::Pos(pos) => pos,
+            LineColLocation::Span(start, _) => start,
+        };
+        let expected = match &err.variant {
+            ErrorVariant::ParsingError { positives, .. } if !positives.is_empty() => positives
+                .iter()
+                .map(|rule| format!("{:?}", rule))
+                .collect::<Vec<_>>()
+                .join(" or "),
+            ErrorVariant::ParsingError { .. } => "end of input".to_string(),
+            ErrorVariant::CustomError { message } => message.clone(),
+        };
+        let rest = err.line().chars().skip(column - 1).collect::<String>();
+
+        Self::new(day, line, column, expected, &rest)
+    }
+
+    /// Blames the start of a token that parsed syntactically but is still invalid.
+    pub fn from_pair<R: RuleType>(
+        day: u32,
+        pair: &Pair<'_, R>,
+        expected: impl Into<String>,
+    ) -> Self {
+        let (line, column) = pair.as_span().start_pos().line_col();
+
+        Self::new(day, line, column, expected, pair.as_str())
+    }
+
+    pub fn from_nom(day: u32, input: &str, err: nom::Err<nom::error::Error<&str>>) -> Self {
+        match err {
+            nom::Err::Error(e) | nom::Err::Failure(e) => {
+                let expected = match e.code {
+                    ErrorKind::CrLf => "line ending".to_string(),
+                    ErrorKind::Digit => "digits".to_string(),
+                    ErrorKind::Eof => "end of input".to_string(),
+                    code => code.description().to_string(),
+                };
+                Self::at_offset(day, input, input.len() - e.input.len(), expected)
+            }
+            nom::Err::Incomplete(_) => Self::at_offset(day, input, input.len(), "more input"),
+        }
+    }
+
+    /// Moves the error to `line` for parsers that are fed one line at a time.
+    pub fn on_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "day {:02}, line {}, column {}: expected {}, found ",
+            self.day, self.line, self.column, self.expected
+        )?;
+        if self.snippet.is_empty() {
+            write!(f, "end of line")
+        } else {
+            write!(f, "{:?}", self.snippet)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a pest token as an integer, blaming the token if it does not fit.
+pub(crate) fn parse_int<R: RuleType>(day: u32, pair: &Pair<'_, R>) -> Result<i64, ParseError> {
+    pair.as_str()
+        .parse::<i64>()
+        .map_err(|_| ParseError::from_pair(day, pair, "integer"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_at_offset() {
+        let input = "first line\nsecond line\n";
+        let err = ParseError::at_offset(3, input, 18, "digit");
+        assert_eq!(err.line, 2);
+        assert_eq!(err.column, 8);
+        assert_eq!(err.snippet, "line");
+        assert_eq!(
+            err.to_string(),
+            "day 03, line 2, column 8: expected digit, found \"line\""
+        );
+    }
+
+    #[test]
+    fn test_at_end_of_input() {
+        let err = ParseError::at_offset(8, "LR", 2, "newline");
+        assert_eq!((err.line, err.column), (1, 3));
+        assert_eq!(
+            err.to_string(),
+            "day 08, line 1, column 3: expected newline, found end of line"
+        );
+    }
+
+    #[test]
+    fn test_snippet_is_truncated() {
+        let err = ParseError::new(1, 1, 1, "digit", &"x".repeat(100));
+        assert_eq!(err.snippet.len(), SNIPPET_LEN);
+    }
+}
@@ -0,0 +1,290 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Dense index of a node, in the order nodes were added.
+pub type NodeId = usize;
+
+/// A directed graph over labels of type `K`, interned to [`NodeId`]s, with ordered
+/// adjacency lists whose edges carry an `E`.
+#[derive(Debug, Clone)]
+pub struct Graph<K, E = ()> {
+    ids: HashMap<K, NodeId>,
+    labels: Vec<K>,
+    edges: Vec<Vec<(NodeId, E)>>,
+}
+
+impl<K, E> Default for Graph<K, E> {
+    fn default() -> Self {
+        Self {
+            ids: HashMap::new(),
+            labels: vec![],
+            edges: vec![],
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash, E> Graph<K, E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interns `label`, returning the id it already had if it was added before.
+    pub fn add_node(&mut self, label: K) -> NodeId {
+        if let Some(&id) = self.ids.get(&label) {
+            return id;
+        }
+        let id = self.labels.len();
+        self.ids.insert(label.clone(), id);
+        self.labels.push(label);
+        self.edges.push(vec![]);
+        id
+    }
+
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId, edge: E) {
+        self.edges[from].push((to, edge));
+    }
+
+    pub fn id(&self, label: &K) -> Option<NodeId> {
+        self.ids.get(label).copied()
+    }
+
+    pub fn label(&self, id: NodeId) -> &K {
+        &self.labels[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn nodes(&self) -> std::ops::Range<NodeId> {
+        0..self.len()
+    }
+
+    /// Outgoing edges of `id` in the order they were added.
+    pub fn edges(&self, id: NodeId) -> &[(NodeId, E)] {
+        &self.edges[id]
+    }
+
+    pub fn neighbours(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.edges[id].iter().map(|(to, _)| *to)
+    }
+
+    /// Nodes reachable from `start` with their distance, nearest first.
+    pub fn bfs(&self, start: NodeId) -> Vec<(NodeId, usize)> {
+        let mut seen = vec![false; self.len()];
+        let mut order = vec![];
+        let mut queue = VecDeque::from([(start, 0)]);
+        seen[start] = true;
+        while let Some((id, distance)) = queue.pop_front() {
+            order.push((id, distance));
+            for next in self.neighbours(id) {
+                if !seen[next] {
+                    seen[next] = true;
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+        order
+    }
+
+    /// Nodes reachable from `start` in depth-first preorder.
+    pub fn dfs(&self, start: NodeId) -> Vec<NodeId> {
+        let mut seen = vec![false; self.len()];
+        let mut order = vec![];
+        let mut stack = vec![start];
+        while let Some(id) = stack.pop() {
+            if std::mem::replace(&mut seen[id], true) {
+                continue;
+            }
+            order.push(id);
+            // Reversed, so the first edge is explored first.
+            stack.extend(self.edges[id].iter().rev().map(|(to, _)| *to));
+        }
+        order
+    }
+
+    /// The nodes along a path with the fewest edges from `from` to `to`, both included.
+    pub fn shortest_path(&self, from: NodeId, to: NodeId) -> Option<Vec<NodeId>> {
+        self.shortest_path_by(from, to, |_| true)
+    }
+
+    /// Like [`Graph::shortest_path`], but only along edges that `follow` accepts.
+    pub fn shortest_path_by(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        follow: impl Fn(&E) -> bool,
+    ) -> Option<Vec<NodeId>> {
+        let mut previous = vec![None; self.len()];
+        let mut queue = VecDeque::from([from]);
+        previous[from] = Some(from);
+        while let Some(id) = queue.pop_front() {
+            if id == to {
+                let mut path = vec![to];
+                while let Some(&last) = path.last().filter(|&&last| last != from) {
+                    path.push(previous[last]?);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for (next, edge) in &self.edges[id] {
+                let next = *next;
+                if follow(edge) && previous[next].is_none() {
+                    previous[next] = Some(id);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Some directed cycle, as the nodes along it, if the graph has one.
+    pub fn find_cycle(&self) -> Option<Vec<NodeId>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum State {
+            New,
+            OnStack,
+            Done,
+        }
+
+        let mut state = vec![State::New; self.len()];
+        for root in self.nodes() {
+            if state[root] != State::New {
+                continue;
+            }
+            // Each frame is a node and the index of its next edge to follow.
+            let mut stack = vec![(root, 0)];
+            state[root] = State::OnStack;
+            while let Some((id, edge)) = stack.last_mut() {
+                let id = *id;
+                let Some(&(next, _)) = self.edges[id].get(*edge) else {
+                    state[id] = State::Done;
+                    stack.pop();
+                    continue;
+                };
+                *edge += 1;
+                match state[next] {
+                    State::New => {
+                        state[next] = State::OnStack;
+                        stack.push((next, 0));
+                    }
+                    State::OnStack => {
+                        let start = stack.iter().position(|(id, _)| *id == next)?;
+                        return Some(stack[start..].iter().map(|(id, _)| *id).collect());
+                    }
+                    State::Done => {}
+                }
+            }
+        }
+        None
+    }
+
+    /// Groups of nodes connected when ignoring edge direction, each sorted by id.
+    pub fn components(&self) -> Vec<Vec<NodeId>> {
+        let mut undirected = vec![vec![]; self.len()];
+        for from in self.nodes() {
+            for to in self.neighbours(from) {
+                undirected[from].push(to);
+                undirected[to].push(from);
+            }
+        }
+
+        let mut component = vec![None; self.len()];
+        let mut components: Vec<Vec<NodeId>> = vec![];
+        for root in self.nodes() {
+            if component[root].is_some() {
+                continue;
+            }
+            let mut members = vec![];
+            let mut stack = vec![root];
+            component[root] = Some(components.len());
+            while let Some(id) = stack.pop() {
+                members.push(id);
+                for &next in &undirected[id] {
+                    if component[next].is_none() {
+                        component[next] = Some(components.len());
+                        stack.push(next);
+                    }
+                }
+            }
+            members.sort();
+            components.push(members);
+        }
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a -> b -> c -> a, c -> d, and e on its own.
+    fn graph() -> Graph<char> {
+        let mut graph = Graph::new();
+        for (from, to) in [('a', 'b'), ('b', 'c'), ('c', 'a'), ('c', 'd')] {
+            let (from, to) = (graph.add_node(from), graph.add_node(to));
+            graph.add_edge(from, to, ());
+        }
+        graph.add_node('e');
+        graph
+    }
+
+    #[test]
+    fn test_interning() {
+        let mut graph = graph();
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph.add_node('c'), 2);
+        assert_eq!(graph.id(&'d'), Some(3));
+        assert_eq!(graph.label(4), &'e');
+    }
+
+    #[test]
+    fn test_traversal() {
+        let graph = graph();
+        assert_eq!(graph.bfs(0), [(0, 0), (1, 1), (2, 2), (3, 3)]);
+        assert_eq!(graph.dfs(1), [1, 2, 0, 3]);
+        assert_eq!(graph.shortest_path(1, 0), Some(vec![1, 2, 0]));
+        assert_eq!(graph.shortest_path(3, 0), None);
+        assert_eq!(graph.shortest_path(4, 4), Some(vec![4]));
+    }
+
+    #[test]
+    fn test_shortest_path_by() {
+        // a -> c directly, or a -> b -> c along edges marked true.
+        let mut graph = Graph::new();
+        let (a, b, c) = (
+            graph.add_node('a'),
+            graph.add_node('b'),
+            graph.add_node('c'),
+        );
+        graph.add_edge(a, c, false);
+        graph.add_edge(a, b, true);
+        graph.add_edge(b, c, true);
+        assert_eq!(graph.shortest_path(a, c), Some(vec![a, c]));
+        assert_eq!(
+            graph.shortest_path_by(a, c, |&edge| edge),
+            Some(vec![a, b, c])
+        );
+        assert_eq!(
+            graph.shortest_path_by(a, c, |&edge| !edge),
+            Some(vec![a, c])
+        );
+        assert_eq!(graph.shortest_path_by(b, c, |&edge| !edge), None);
+    }
+
+    #[test]
+    fn test_cycles_and_components() {
+        let mut graph = graph();
+        assert_eq!(graph.find_cycle(), Some(vec![0, 1, 2]));
+        assert_eq!(graph.components(), [vec![0, 1, 2, 3], vec![4]]);
+
+        graph = Graph::new();
+        let (a, b) = (graph.add_node('a'), graph.add_node('b'));
+        graph.add_edge(a, b, ());
+        assert_eq!(graph.find_cycle(), None);
+    }
+}
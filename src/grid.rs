@@ -0,0 +1,299 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+
+use crate::error::ParseError;
+
+/// A cell's `(x, y)` coordinates, with `y` growing downwards.
+pub type Pos = (usize, usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dir {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir {
+    /// Clockwise, starting at [`Dir::Up`].
+    pub const ALL: [Dir; 4] = [Dir::Up, Dir::Right, Dir::Down, Dir::Left];
+
+    pub fn opposite(self) -> Dir {
+        match self {
+            Dir::Up => Dir::Down,
+            Dir::Right => Dir::Left,
+            Dir::Down => Dir::Up,
+            Dir::Left => Dir::Right,
+        }
+    }
+
+    fn offset(self) -> (isize, isize) {
+        match self {
+            Dir::Up => (0, -1),
+            Dir::Right => (1, 0),
+            Dir::Down => (0, 1),
+            Dir::Left => (-1, 0),
+        }
+    }
+}
+
+/// Offsets of the eight cells around a cell, row by row.
+const AROUND: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A rectangular grid of cells stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Panics if `cells` does not hold exactly `width * height` cells.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "grid cells do not fit its size"
+        );
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::new(width, height, vec![value; width * height])
+    }
+
+    /// Parses one line per row and one char per cell, `cell` returning `None` for a
+    /// char that is not `expected`. Rows must all have the same width.
+    pub fn parse(
+        day: u32,
+        input: &str,
+        expected: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut cells = vec![];
+        let mut height = 0;
+        for (y, line) in input.lines().enumerate() {
+            let row_start = cells.len();
+            for (x, c) in line.chars().enumerate() {
+                let value = cell(c).ok_or_else(|| {
+                    let rest = line.chars().skip(x).collect::<String>();
+                    ParseError::new(day, y + 1, x + 1, expected, &rest)
+                })?;
+                cells.push(value);
+            }
+            let row_width = cells.len() - row_start;
+            match width {
+                None => width = Some(row_width),
+                Some(width) if width != row_width => {
+                    let (column, rest) = match line.char_indices().nth(width) {
+                        Some((i, _)) => (width + 1, &line[i..]),
+                        None => (row_width + 1, ""),
+                    };
+                    return Err(ParseError::new(
+                        day,
+                        y + 1,
+                        column,
+                        format!("row of width {}", width),
+                        rest,
+                    ));
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Self::new(width, height, cells)),
+            _ => Err(ParseError::at_offset(day, input, 0, "grid")),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.0 + pos.1 * self.width])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.contains(pos)
+            .then(|| &mut self.cells[pos.0 + pos.1 * self.width])
+    }
+
+    fn offset(&self, (x, y): Pos, (dx, dy): (isize, isize)) -> Option<Pos> {
+        let pos = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.contains(pos).then_some(pos)
+    }
+
+    /// The neighbour of `pos` in direction `dir`, if it lies inside the grid.
+    pub fn step(&self, pos: Pos, dir: Dir) -> Option<Pos> {
+        self.offset(pos, dir.offset())
+    }
+
+    /// The up to four orthogonal neighbours of `pos`, clockwise from above.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Dir::ALL
+            .into_iter()
+            .filter_map(move |dir| self.step(pos, dir))
+    }
+
+    /// The up to eight orthogonal and diagonal neighbours of `pos`, row by row.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        AROUND
+            .into_iter()
+            .filter_map(move |offset| self.offset(pos, offset))
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .skip(x)
+            .step_by(width.max(1))
+            .take(if x < width { self.height } else { 0 })
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", pos))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", pos))
+    }
+}
+
+/// One line per row, cells written next to each other.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        Grid::parse(0, "abc\ndef\n", "letter", Some).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_print() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 'f');
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.to_string(), "abc\ndef");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Grid::parse(0, "ab\nabc\n", "letter", Some).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.expected, "row of width 2");
+
+        let err =
+            Grid::parse(0, "ab\na?\n", "letter", |c| c.is_alphabetic().then_some(c)).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert!(Grid::parse(0, "", "letter", Some).is_err());
+    }
+
+    #[test]
+    fn test_neighbours_are_bounds_checked() {
+        let grid = grid();
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            [(1, 0), (0, 1)]
+        );
+        assert_eq!(
+            grid.neighbours8((2, 1)).collect::<Vec<_>>(),
+            [(1, 0), (2, 0), (1, 1)]
+        );
+        assert_eq!(grid.neighbours8((1, 0)).count(), 5);
+        assert_eq!(grid.step((0, 0), Dir::Left), None);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = grid();
+        assert_eq!(grid.row(1), Some(&['d', 'e', 'f'][..]));
+        assert_eq!(
+            grid.columns()
+                .map(|c| c.collect::<String>())
+                .collect::<Vec<_>>(),
+            ["ad", "be", "cf"]
+        );
+        assert_eq!(grid.column(3).count(), 0);
+    }
+}
@@ -0,0 +1,203 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+
+/// Environment variable overriding the directory puzzle inputs are read from.
+pub const INPUT_DIR_VAR: &str = "AOC23_INPUTS";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Kind {
+    Example,
+    Real,
+}
+
+impl Kind {
+    fn suffix(self) -> &'static str {
+        match self {
+            Kind::Example => "ex",
+            Kind::Real => "in",
+        }
+    }
+}
+
+/// An input named by convention, e.g. `day08_p1_ex2` is the second example of day 8 part 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InputFile {
+    pub day: u32,
+    pub part: u32,
+    pub kind: Kind,
+    /// Starts at 1, which has no number in the file name.
+    pub variant: u32,
+}
+
+impl InputFile {
+    pub fn new(day: u32, part: u32, kind: Kind) -> Self {
+        Self {
+            day,
+            part,
+            kind,
+            variant: 1,
+        }
+    }
+
+    pub fn with_variant(self, variant: u32) -> Self {
+        Self { variant, ..self }
+    }
+
+    pub fn path(&self) -> PathBuf {
+        dir().join(self.to_string())
+    }
+}
+
+impl Display for InputFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "day{:02}_p{}_{}",
+            self.day,
+            self.part,
+            self.kind.suffix()
+        )?;
+        if self.variant > 1 {
+            write!(f, "{}", self.variant)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for InputFile {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || anyhow!("Not an input file name: {}", s);
+
+        let (day, rest) = s
+            .strip_prefix("day")
+            .and_then(|s| s.split_once("_p"))
+            .ok_or_else(invalid)?;
+        let (part, rest) = rest.split_once('_').ok_or_else(invalid)?;
+        let (kind, variant) = if let Some(variant) = rest.strip_prefix("ex") {
+            (Kind::Example, variant)
+        } else if let Some(variant) = rest.strip_prefix("in") {
+            (Kind::Real, variant)
+        } else {
+            return Err(invalid());
+        };
+
+        let number = |n: &str| n.parse::<u32>().map_err(|_| invalid());
+        let file = InputFile {
+            day: number(day)?,
+            part: number(part)?,
+            kind,
+            variant: if variant.is_empty() {
+                1
+            } else {
+                number(variant)?
+            },
+        };
+
+        // Only the canonical spelling names the file that `path` leads back to.
+        if file.to_string() != s {
+            return Err(invalid());
+        }
+        Ok(file)
+    }
+}
+
+/// Directory holding the inputs, `$AOC23_INPUTS` or else the working directory.
+pub fn dir() -> PathBuf {
+    std::env::var_os(INPUT_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Finds the input for `file`, falling back to part 1's file since part 2 often reuses it.
+pub fn locate(file: InputFile) -> Result<PathBuf> {
+    locate_in(&dir(), file)
+}
+
+fn locate_in(dir: &Path, file: InputFile) -> Result<PathBuf> {
+    let fallback = InputFile { part: 1, ..file };
+    [file, fallback]
+        .iter()
+        .map(|f| dir.join(f.to_string()))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            anyhow!(
+                "Input file not found: {}",
+                dir.join(file.to_string()).display()
+            )
+        })
+}
+
+/// Every input of `day` present in the input directory, sorted by part, kind and variant.
+pub fn list(day: u32) -> Result<Vec<InputFile>> {
+    list_in(&dir(), day)
+}
+
+fn list_in(dir: &Path, day: u32) -> Result<Vec<InputFile>> {
+    let mut files = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<InputFile>().ok())
+        .filter(|file| file.day == day)
+        .collect::<Vec<_>>();
+    files.sort();
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name_round_trip() -> Result<()> {
+        for name in ["day05_p1_ex", "day06_p2_in", "day08_p1_ex2"] {
+            assert_eq!(name.parse::<InputFile>()?.to_string(), name);
+        }
+        assert_eq!(
+            "day08_p1_ex2".parse::<InputFile>()?,
+            InputFile::new(8, 1, Kind::Example).with_variant(2)
+        );
+        assert!("day08_p1_out".parse::<InputFile>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_non_canonical_names_rejected() {
+        for name in [
+            "day8_p1_in",
+            "day08_p1_ex1",
+            "day08_p1_ex02",
+            "day08_p01_in",
+            "day08_p1_ex0",
+        ] {
+            assert!(name.parse::<InputFile>().is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_locate_falls_back_to_part1() -> Result<()> {
+        let dir = Path::new(".");
+        let located = locate_in(dir, InputFile::new(6, 2, Kind::Real))?;
+        assert_eq!(located, dir.join("day06_p2_in"));
+        let located = locate_in(dir, InputFile::new(3, 2, Kind::Real))?;
+        assert_eq!(located, dir.join("day03_p1_in"));
+        assert!(locate_in(dir, InputFile::new(3, 2, Kind::Real).with_variant(9)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_list() -> Result<()> {
+        let names = list_in(Path::new("."), 8)?
+            .iter()
+            .map(InputFile::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["day08_p1_ex", "day08_p1_ex2", "day08_p1_in", "day08_p2_ex"]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+/// A set of integers stored as disjoint, non-adjacent half-open ranges, sorted by
+/// start so that operations on `k` ranges against a set of `n` take `O(k log n)`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    /// Start of each range to its end.
+    ranges: BTreeMap<i64, i64>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of disjoint ranges.
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Number of integers in the set.
+    pub fn size(&self) -> i64 {
+        self.iter().map(|range| range.end - range.start).sum()
+    }
+
+    pub fn min(&self) -> Option<i64> {
+        self.ranges.keys().next().copied()
+    }
+
+    pub fn max(&self) -> Option<i64> {
+        self.ranges.values().next_back().map(|end| end - 1)
+    }
+
+    /// The ranges in order.
+    pub fn iter(&self) -> impl Iterator<Item = Range<i64>> + '_ {
+        self.ranges.iter().map(|(&start, &end)| start..end)
+    }
+
+    /// The range reaching furthest right that starts at or before `point`.
+    fn before(&self, point: i64) -> Option<Range<i64>> {
+        self.ranges
+            .range(..=point)
+            .next_back()
+            .map(|(&start, &end)| start..end)
+    }
+
+    pub fn contains(&self, point: i64) -> bool {
+        self.before(point).is_some_and(|range| range.end > point)
+    }
+
+    /// Whether every integer of `range` is in the set.
+    pub fn contains_range(&self, range: Range<i64>) -> bool {
+        range.is_empty() || self.before(range.start).is_some_and(|r| r.end >= range.end)
+    }
+
+    /// Whether any integer of `range` is in the set.
+    pub fn overlaps(&self, range: Range<i64>) -> bool {
+        !range.is_empty()
+            && self
+                .ranges
+                .range(..range.end)
+                .next_back()
+                .is_some_and(|(_, &end)| end > range.start)
+    }
+
+    /// The parts of the set inside `range`, in order.
+    pub fn within(&self, range: Range<i64>) -> impl Iterator<Item = Range<i64>> + '_ {
+        let first = self
+            .before(range.start)
+            .filter(|r| r.start < range.start && !range.is_empty());
+        let rest = self
+            .ranges
+            .range(range.start..range.end.max(range.start))
+            .map(|(&start, &end)| start..end);
+        first
+            .into_iter()
+            .chain(rest)
+            .map(move |r| r.start.max(range.start)..r.end.min(range.end))
+            .filter(|r| !r.is_empty())
+    }
+
+    pub fn insert(&mut self, range: Range<i64>) {
+        if range.is_empty() {
+            return;
+        }
+        let (mut start, mut end) = (range.start, range.end);
+        if let Some(before) = self.before(start).filter(|r| r.end >= start) {
+            start = before.start;
+            end = end.max(before.end);
+        }
+        let merged = self
+            .ranges
+            .range(start..=end)
+            .map(|(&start, &end)| (start, end))
+            .collect::<Vec<_>>();
+        for (s, e) in merged {
+            self.ranges.remove(&s);
+            end = end.max(e);
+        }
+        self.ranges.insert(start, end);
+    }
+
+    pub fn remove(&mut self, range: Range<i64>) {
+        if range.is_empty() {
+            return;
+        }
+        if let Some(before) = self.before(range.start).filter(|r| r.end > range.start) {
+            if before.start < range.start {
+                self.ranges.insert(before.start, range.start);
+                if before.end > range.end {
+                    self.ranges.insert(range.end, before.end);
+                    return;
+                }
+            }
+        }
+        let cut = self
+            .ranges
+            .range(range.start..range.end)
+            .map(|(&start, &end)| (start, end))
+            .collect::<Vec<_>>();
+        for (start, end) in cut {
+            self.ranges.remove(&start);
+            if end > range.end {
+                self.ranges.insert(range.end, end);
+            }
+        }
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let (mut larger, smaller) = match self.len() >= other.len() {
+            true => (self.clone(), other),
+            false => (other.clone(), self),
+        };
+        for range in smaller.iter() {
+            larger.insert(range);
+        }
+        larger
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (larger, smaller) = match self.len() >= other.len() {
+            true => (self, other),
+            false => (other, self),
+        };
+        smaller
+            .iter()
+            .flat_map(|range| larger.within(range))
+            .collect()
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for range in other.iter() {
+            difference.remove(range);
+        }
+        difference
+    }
+
+    /// Everything in `bound` that is not in the set.
+    pub fn complement(&self, bound: Range<i64>) -> Self {
+        IntervalSet::from(bound).difference(self)
+    }
+
+    /// Every integer moved by `offset`.
+    pub fn shifted(&self, offset: i64) -> Self {
+        Self {
+            ranges: self
+                .ranges
+                .iter()
+                .map(|(&start, &end)| (start + offset, end + offset))
+                .collect(),
+        }
+    }
+}
+
+impl From<Range<i64>> for IntervalSet {
+    fn from(range: Range<i64>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl FromIterator<Range<i64>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Range<i64>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+/// Inclusive bounds like `[1, 3] ∪ [7, 7]`, or `∅`.
+impl Display for IntervalSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "∅");
+        }
+        for (i, range) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ∪ ")?;
+            }
+            write!(f, "[{}, {}]", range.start, range.end - 1)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[Range<i64>]) -> IntervalSet {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_insert_normalizes() {
+        let set = set(&[5..8, 0..2, 2..3, 7..10, 12..12]);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0..3, 5..10]);
+        assert_eq!(set.to_string(), "[0, 2] ∪ [5, 9]");
+        assert_eq!(
+            (set.len(), set.size(), set.min(), set.max()),
+            (2, 8, Some(0), Some(9))
+        );
+    }
+
+    #[test]
+    fn test_membership() {
+        let set = set(&[0..3, 5..10]);
+        assert!(set.contains(0) && set.contains(9) && !set.contains(3) && !set.contains(10));
+        assert!(set.contains_range(5..10) && !set.contains_range(2..6));
+        assert!(set.overlaps(2..6) && !set.overlaps(3..5) && !set.overlaps(4..4));
+        assert_eq!(set.within(1..7).collect::<Vec<_>>(), [1..3, 5..7]);
+    }
+
+    #[test]
+    fn test_remove_splits() {
+        let mut set = set(&[0..10, 20..30]);
+        set.remove(3..5);
+        set.remove(8..22);
+        set.remove(29..40);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0..3, 5..8, 22..29]);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[0..5, 10..15]);
+        let b = set(&[3..12, 20..25]);
+        assert_eq!(a.union(&b), set(&[0..15, 20..25]));
+        assert_eq!(a.intersection(&b), set(&[3..5, 10..12]));
+        assert_eq!(a.difference(&b), set(&[0..3, 12..15]));
+        assert_eq!(a.complement(-5..20), set(&[-5..0, 5..10, 15..20]));
+        assert_eq!(a.shifted(-10), set(&[-10..-5, 0..5]));
+    }
+}
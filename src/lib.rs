@@ -0,0 +1,46 @@
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+
+pub mod answers;
+pub mod bench;
+pub mod error;
+pub mod graph;
+pub mod grid;
+pub mod inputs;
+pub mod interval;
+pub mod output;
+mod solution;
+
+pub use error::ParseError;
+pub use solution::Solution;
+
+/// Every day with a solution.
+pub const DAYS: std::ops::RangeInclusive<u32> = 1..=10;
+
+/// Calls `$f::<Solution>(args...)` with the solution type of `$day`.
+#[macro_export]
+macro_rules! dispatch {
+    ($day:expr, $f:ident($($arg:expr),*)) => {
+        match $day {
+            1 => $f::<$crate::day01::CalibrationDocument>($($arg),*),
+            2 => $f::<$crate::day02::GameRecord>($($arg),*),
+            3 => $f::<$crate::day03::Schematic>($($arg),*),
+            4 => $f::<$crate::day04::Scratchcards>($($arg),*),
+            5 => $f::<$crate::day05::Almanac>($($arg),*),
+            6 => $f::<$crate::day06::RaceTable>($($arg),*),
+            7 => $f::<$crate::day07::CamelCards>($($arg),*),
+            8 => $f::<$crate::day08::Network>($($arg),*),
+            9 => $f::<$crate::day09::Report>($($arg),*),
+            10 => $f::<$crate::day10::Maze>($($arg),*),
+            day => Err(::anyhow::anyhow!("No solution for day {}", day)),
+        }
+    };
+}
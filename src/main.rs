@@ -0,0 +1,393 @@
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use clap::{ArgGroup, Parser, ValueEnum};
+
+use aoc23::answers::{Answers, Verdict, ANSWERS_FILE};
+use aoc23::bench::{bench, CountingAlloc, DayBench, Report};
+use aoc23::day08::jump::JumpTable;
+use aoc23::day08::{cycles, dot, Ends};
+use aoc23::day10::render::{Render, RenderFormat};
+use aoc23::inputs::{self, InputFile, Kind};
+use aoc23::output::{self, Format, Record};
+use aoc23::*;
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum PartArg {
+    #[value(name = "1")]
+    One,
+    #[value(name = "2")]
+    Two,
+    All,
+}
+
+#[derive(Parser, Debug)]
+#[command(name = "aoc23", about = "Advent of Code 2023 solutions")]
+#[command(group(ArgGroup::new("network").args(["cycles", "dot", "at"])))]
+struct Args {
+    /// Day to run (1-10), all days without it
+    #[arg(short, long)]
+    day: Option<u32>,
+
+    /// Part to run
+    #[arg(short, long, value_enum, default_value = "all")]
+    part: PartArg,
+
+    /// Puzzle input file or `-` for stdin, defaults to the day's `dayNN_pP_in`
+    #[arg(short, long, requires = "day")]
+    input: Option<String>,
+
+    /// Run on the day's example input instead, optionally the given variant
+    #[arg(short, long, num_args = 0..=1, default_missing_value = "1", conflicts_with = "input")]
+    example: Option<u32>,
+
+    /// List the day's inputs found in the input directory (`$AOC23_INPUTS` or `.`)
+    #[arg(long, requires = "day")]
+    list: bool,
+
+    /// Redraw the day 10 pipe maze instead of solving it
+    #[arg(long, value_enum, requires = "day")]
+    render: Option<RenderFormat>,
+
+    /// Report the cycle each day 8 ghost falls into instead of solving it, in `--format`
+    #[arg(long, requires = "day", conflicts_with = "render")]
+    cycles: bool,
+
+    /// Write the day 8 network as Graphviz DOT instead of solving it
+    #[arg(long, requires = "day", conflicts_with_all = ["render", "cycles"])]
+    dot: bool,
+
+    /// Highlight the path part 1 takes in the `--dot` output
+    #[arg(long, requires = "dot")]
+    with_path: bool,
+
+    /// Print where each day 8 start node is after this many steps instead of solving it
+    #[arg(long, value_name = "STEPS", requires = "day", conflicts_with_all = ["render", "cycles", "dot"])]
+    at: Option<u64>,
+
+    /// Day 8 start nodes for `--cycles`, `--dot` and `--at`: an id, `*suffix` or `/regex/`
+    #[arg(long, requires = "network")]
+    start: Option<String>,
+
+    /// Day 8 end nodes for `--cycles`, `--dot` and `--at`: an id, `*suffix` or `/regex/`
+    #[arg(long, requires = "network")]
+    end: Option<String>,
+
+    /// How to print answers
+    #[arg(short, long, value_enum, default_value = "plain")]
+    format: Format,
+
+    /// Log solver internals to stderr (`-v` debug, `-vv` trace), overrides `RUST_LOG`
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Time parse, part 1 and part 2 instead of printing answers
+    #[arg(long)]
+    bench: bool,
+
+    /// Iterations per day in `--bench` mode
+    #[arg(short = 'n', long, default_value_t = 10)]
+    iterations: usize,
+}
+
+fn init_logging(verbose: u8) {
+    let filter = match verbose {
+        0 => "warn",
+        1 => "aoc23=debug",
+        _ => "aoc23=trace",
+    };
+    let mut builder =
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(filter));
+    if verbose > 0 {
+        builder.parse_filters(filter);
+    }
+    builder.format_timestamp(None).init();
+}
+
+fn read_input(input: &str) -> Result<String> {
+    if input == "-" {
+        let mut buf = String::new();
+        std::io::stdin().read_to_string(&mut buf)?;
+        return Ok(buf);
+    }
+
+    if !std::path::Path::new(input).is_file() {
+        return Err(anyhow!("Input file not found: {}", input));
+    }
+    Ok(std::fs::read_to_string(input)?)
+}
+
+/// The conventional input of `day` and `part`, honouring `--example`.
+fn default_input(args: &Args, day: u32, part: u32) -> Result<String> {
+    let kind = match args.example {
+        Some(_) => Kind::Example,
+        None => Kind::Real,
+    };
+    let file = InputFile::new(day, part, kind).with_variant(args.example.unwrap_or(1));
+
+    Ok(inputs::locate(file)?.display().to_string())
+}
+
+/// Pairs each input with the parts to solve on it, parsing a shared input only once.
+fn inputs_for(args: &Args, day: u32) -> Result<Vec<(String, PartArg)>> {
+    if let Some(input) = &args.input {
+        return Ok(vec![(input.clone(), args.part)]);
+    }
+
+    Ok(match args.part {
+        PartArg::One => vec![(default_input(args, day, 1)?, PartArg::One)],
+        PartArg::Two => vec![(default_input(args, day, 2)?, PartArg::Two)],
+        PartArg::All => {
+            let (part1, part2) = (default_input(args, day, 1)?, default_input(args, day, 2)?);
+            if part1 == part2 {
+                vec![(part1, PartArg::All)]
+            } else {
+                vec![(part1, PartArg::One), (part2, PartArg::Two)]
+            }
+        }
+    })
+}
+
+fn timed(f: impl FnOnce() -> Result<i64>) -> Result<(i64, Duration)> {
+    let start = Instant::now();
+    let answer = f()?;
+    Ok((answer, start.elapsed()))
+}
+
+/// Solves the requested parts, returning `(part, answer, elapsed)` triples.
+fn run<S: Solution>(input: &str, part: PartArg) -> Result<Vec<(u32, i64, Duration)>> {
+    let parsed = S::parse(input)?;
+
+    let mut answers = vec![];
+    if part != PartArg::Two {
+        let (answer, elapsed) = timed(|| S::part1(&parsed))?;
+        answers.push((1, answer, elapsed));
+    }
+    if part != PartArg::One {
+        let (answer, elapsed) = timed(|| S::part2(&parsed))?;
+        answers.push((2, answer, elapsed));
+    }
+
+    Ok(answers)
+}
+
+fn solve_day(known: &Answers, day: u32, inputs: Vec<(String, PartArg)>) -> Result<Vec<Record>> {
+    let mut records = vec![];
+    for (input_file, part) in inputs {
+        let input = read_input(&input_file)?;
+        for (part, answer, elapsed) in dispatch!(day, run(&input, part))? {
+            records.push(Record {
+                day,
+                part,
+                answer,
+                elapsed,
+                verdict: known.verdict(day, part, &input_file, answer),
+                input: input_file.clone(),
+            });
+        }
+    }
+
+    Ok(records)
+}
+
+/// The input files part 1 and part 2 are benched on.
+fn bench_inputs(args: &Args, day: u32) -> Result<(String, String)> {
+    Ok(match &args.input {
+        Some(input) => (input.clone(), input.clone()),
+        None => (default_input(args, day, 1)?, default_input(args, day, 2)?),
+    })
+}
+
+/// Benches each part on its own input, reading a shared input only once.
+fn bench_day(args: &Args, day: u32, (part1, part2): (String, String)) -> Result<DayBench> {
+    let input1 = read_input(&part1)?;
+    let input2 = match part1 == part2 {
+        true => input1.clone(),
+        false => read_input(&part2)?,
+    };
+    dispatch!(day, bench(day, &input1, &input2, args.iterations))
+}
+
+/// Runs `run` on the inputs `locate` finds for `--day`, or for every day without it.
+///
+/// When running every day, days whose inputs are missing are skipped, while any other
+/// failure is reported and its day returned alongside the results of the others.
+fn for_days<I, T>(
+    args: &Args,
+    locate: impl Fn(u32) -> Result<I>,
+    run: impl Fn(u32, I) -> Result<T>,
+) -> Result<(Vec<T>, Vec<u32>)> {
+    if let Some(day) = args.day {
+        return Ok((vec![run(day, locate(day)?)?], vec![]));
+    }
+
+    let (mut results, mut failed) = (vec![], vec![]);
+    for day in DAYS {
+        let inputs = match locate(day) {
+            Ok(inputs) => inputs,
+            Err(e) => {
+                eprintln!("day {:02}: skipped, {}", day, e);
+                continue;
+            }
+        };
+        match run(day, inputs) {
+            Ok(result) => results.push(result),
+            Err(e) => {
+                eprintln!("day {:02}: failed, {:#}", day, e);
+                failed.push(day);
+            }
+        }
+    }
+
+    Ok((results, failed))
+}
+
+fn check_failed(failed: &[u32]) -> Result<()> {
+    if failed.is_empty() {
+        return Ok(());
+    }
+    let days = failed
+        .iter()
+        .map(|day| format!("{:02}", day))
+        .collect::<Vec<_>>();
+    Err(anyhow!("day(s) {} failed", days.join(", ")))
+}
+
+fn bench_days(args: &Args) -> Result<()> {
+    let (benches, failed) = for_days(
+        args,
+        |day| bench_inputs(args, day),
+        |day, files| bench_day(args, day, files),
+    )?;
+
+    println!("{}", Report(&benches));
+    check_failed(&failed)
+}
+
+/// `default`, with its start and end replaced by those given on the command line.
+fn ends(args: &Args, default: Ends) -> Result<Ends> {
+    Ok(Ends {
+        start: match &args.start {
+            Some(start) => start.parse()?,
+            None => default.start,
+        },
+        end: match &args.end {
+            Some(end) => end.parse()?,
+            None => default.end,
+        },
+    })
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    init_logging(args.verbose);
+
+    if args.bench {
+        return bench_days(&args);
+    }
+
+    if args.list {
+        let day = args.day.expect("clap requires --day with --list");
+        for file in inputs::list(day)? {
+            println!("{}", file.path().display());
+        }
+        return Ok(());
+    }
+
+    if let Some(format) = args.render {
+        let day = args.day.expect("clap requires --day with --render");
+        if day != 10 {
+            return Err(anyhow!("Only day 10 can be rendered"));
+        }
+        let input_file = match &args.input {
+            Some(input) => input.clone(),
+            None => default_input(&args, day, 1)?,
+        };
+        let maze = day10::Maze::parse(&read_input(&input_file)?)?;
+        return Render::new(&maze)?.write(format, &mut std::io::stdout().lock());
+    }
+
+    if args.cycles {
+        let day = args.day.expect("clap requires --day with --cycles");
+        if day != 8 {
+            return Err(anyhow!("Only day 8 has a cycle report"));
+        }
+        let input_file = match &args.input {
+            Some(input) => input.clone(),
+            None => default_input(&args, day, 2)?,
+        };
+        let network = day08::Network::parse(&read_input(&input_file)?)?;
+        let analysis = cycles::analyse(&network, &ends(&args, Ends::ghosts())?)?;
+        return cycles::write(args.format, &analysis, &mut std::io::stdout().lock());
+    }
+
+    if args.dot {
+        let day = args.day.expect("clap requires --day with --dot");
+        if day != 8 {
+            return Err(anyhow!("Only day 8 can be exported as DOT"));
+        }
+        let input_file = match &args.input {
+            Some(input) => input.clone(),
+            None => default_input(&args, day, 1)?,
+        };
+        let network = day08::Network::parse(&read_input(&input_file)?)?;
+        let path = match args.with_path {
+            true => Some(network.path(&ends(&args, Ends::camel())?)?),
+            false => None,
+        };
+        print!(
+            "{}",
+            dot::dot(&network, &ends(&args, Ends::ghosts())?, path.as_deref())?
+        );
+        return Ok(());
+    }
+
+    if let Some(steps) = args.at {
+        let day = args.day.expect("clap requires --day with --at");
+        if day != 8 {
+            return Err(anyhow!("Only day 8 can be walked with --at"));
+        }
+        let input_file = match &args.input {
+            Some(input) => input.clone(),
+            None => default_input(&args, day, 2)?,
+        };
+        let network = day08::Network::parse(&read_input(&input_file)?)?;
+        let ends = ends(&args, Ends::ghosts())?;
+        let is_end = network.mask(&ends.end);
+        let table = JumpTable::new(&network);
+        for start in network.matching(&ends.start) {
+            let id = table.at(start, steps).ok_or(anyhow!(
+                "The walk from {} has to follow a missing child within {} steps",
+                network.name(start),
+                steps
+            ))?;
+            let end = if is_end[id] { " (end)" } else { "" };
+            println!("{} -> {}{}", network.name(start), network.name(id), end);
+        }
+        return Ok(());
+    }
+
+    let known = Answers::load(ANSWERS_FILE)?;
+
+    let (records, failed) = for_days(
+        &args,
+        |day| inputs_for(&args, day),
+        |day, inputs| solve_day(&known, day, inputs),
+    )?;
+    let records = records.concat();
+    output::write(args.format, &records, &mut std::io::stdout().lock())?;
+    check_failed(&failed)?;
+
+    let wrong = records
+        .iter()
+        .filter(|r| matches!(r.verdict, Verdict::Wrong { .. }))
+        .count();
+    if wrong > 0 {
+        return Err(anyhow!("{} answer(s) differ from {}", wrong, ANSWERS_FILE));
+    }
+    Ok(())
+}
@@ -0,0 +1,179 @@
+use std::io::Write;
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::answers::Verdict;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// `Part 1: 55172 ✓` lines, grouped by day when running several
+    #[default]
+    Plain,
+    /// An array with one object per day and part
+    Json,
+    /// A header and one row per day and part
+    Csv,
+}
+
+/// The answer of one part of a day, as reported by the runner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub day: u32,
+    pub part: u32,
+    pub answer: i64,
+    /// Time spent in the part itself, without parsing the input.
+    pub elapsed: Duration,
+    pub input: String,
+    pub verdict: Verdict,
+}
+
+/// Flat form of a [`Record`] shared by the JSON and CSV output.
+#[derive(Serialize)]
+struct Row<'a> {
+    day: u32,
+    part: u32,
+    answer: i64,
+    elapsed_ms: f64,
+    input: &'a str,
+    status: &'static str,
+    expected: Option<i64>,
+}
+
+impl<'a> From<&'a Record> for Row<'a> {
+    fn from(record: &'a Record) -> Self {
+        let (status, expected) = match record.verdict {
+            Verdict::Unknown => ("unknown", None),
+            Verdict::Correct => ("correct", Some(record.answer)),
+            Verdict::Wrong { expected } => ("wrong", Some(expected)),
+        };
+        Row {
+            day: record.day,
+            part: record.part,
+            answer: record.answer,
+            elapsed_ms: record.elapsed.as_secs_f64() * 1000.0,
+            input: &record.input,
+            status,
+            expected,
+        }
+    }
+}
+
+const CSV_HEADER: &str = "day,part,answer,elapsed_ms,input,status,expected";
+
+/// Quotes a CSV field if it contains a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn write(format: Format, records: &[Record], out: &mut impl Write) -> Result<()> {
+    match format {
+        Format::Plain => {
+            let several_days = records.iter().any(|r| r.day != records[0].day);
+            let mut day = None;
+            for record in records {
+                if several_days && day != Some(record.day) {
+                    writeln!(out, "Day {:02}", record.day)?;
+                    day = Some(record.day);
+                }
+                match record.verdict {
+                    Verdict::Unknown => writeln!(out, "Part {}: {}", record.part, record.answer)?,
+                    verdict => {
+                        writeln!(out, "Part {}: {} {}", record.part, record.answer, verdict)?
+                    }
+                }
+            }
+        }
+        Format::Json => {
+            let rows = records.iter().map(Row::from).collect::<Vec<_>>();
+            serde_json::to_writer_pretty(&mut *out, &rows)?;
+            writeln!(out)?;
+        }
+        Format::Csv => {
+            writeln!(out, "{}", CSV_HEADER)?;
+            for row in records.iter().map(Row::from) {
+                writeln!(
+                    out,
+                    "{},{},{},{:.3},{},{},{}",
+                    row.day,
+                    row.part,
+                    row.answer,
+                    row.elapsed_ms,
+                    csv_field(row.input),
+                    row.status,
+                    row.expected.map(|e| e.to_string()).unwrap_or_default()
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                day: 2,
+                part: 1,
+                answer: 8,
+                elapsed: Duration::from_micros(1500),
+                input: "./day02_p1_ex".to_string(),
+                verdict: Verdict::Correct,
+            },
+            Record {
+                day: 6,
+                part: 2,
+                answer: 5,
+                elapsed: Duration::from_millis(2),
+                input: "inputs/a,b".to_string(),
+                verdict: Verdict::Wrong { expected: 7 },
+            },
+        ]
+    }
+
+    fn written(format: Format) -> Result<String> {
+        let mut out = vec![];
+        write(format, &records(), &mut out)?;
+        Ok(String::from_utf8(out)?)
+    }
+
+    #[test]
+    fn test_plain() -> Result<()> {
+        assert_eq!(
+            written(Format::Plain)?,
+            "Day 02\nPart 1: 8 ✓\nDay 06\nPart 2: 5 ✗ (expected 7)\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_json() -> Result<()> {
+        let json: serde_json::Value = serde_json::from_str(&written(Format::Json)?)?;
+        assert_eq!(json[0]["elapsed_ms"], 1.5);
+        assert_eq!(json[0]["status"], "correct");
+        assert_eq!(json[1]["expected"], 7);
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv() -> Result<()> {
+        assert_eq!(
+            written(Format::Csv)?,
+            format!(
+                "{}\n2,1,8,1.500,./day02_p1_ex,correct,8\n6,2,5,2.000,\"inputs/a,b\",wrong,7\n",
+                CSV_HEADER
+            )
+        );
+        Ok(())
+    }
+}
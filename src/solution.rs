@@ -0,0 +1,18 @@
+use anyhow::Result;
+
+/// A single day's puzzle: parse the input once, then solve either part on it.
+pub trait Solution {
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+    fn part1(input: &Self::Input) -> Result<i64>;
+    fn part2(input: &Self::Input) -> Result<i64>;
+
+    fn solve_part1(input: &str) -> Result<i64> {
+        Self::part1(&Self::parse(input)?)
+    }
+
+    fn solve_part2(input: &str) -> Result<i64> {
+        Self::part2(&Self::parse(input)?)
+    }
+}